@@ -1,19 +1,69 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::path::Path;
+use std::time::Duration;
 
-use anyhow::{anyhow, Context};
-use chrono::{DateTime, Datelike, Months, NaiveDateTime, Timelike, Utc, Weekday};
-use lazy_static::lazy_static;
+use chrono::{DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, NaiveTime, Offset, Timelike, Utc, Weekday};
+use glob::Pattern;
 
-use crate::traits::CommitStatsExt;
+use crate::ser::sorted_entries;
+use crate::traits::{CommitStatsExt, Merge};
 use crate::{
-	Author, CommitArgs, CommitArgsBuilder, CommitDetail, CommitHash, CommitStats, CommitsHeatMap, CommitsPerAuthor,
-	CommitsPerDayHour, CommitsPerMonth, CommitsPerWeekday, Detail, GlobalStat, MinimalCommitDetail, SimpleStat, SortStatsBy,
+	Author, Churn, CoAuthorMode, CommitArgs, CommitArgsBuilder, CommitDetail, CommitHash, CommitStats, CommitsHeatMap,
+	CommitsPerAuthor, CommitsPerDayHour, CommitsPerMonth, CommitsPerWeek, CommitsPerWeekday, Detail, Error, FileStat,
+	GlobalStat, HistoryScope, LandingDelayStats, MinimalCommitDetail, Shares, SimpleStat, SizeBucket, SizeHistogram,
+	SortOrder, SortStatsBy, StatsPerExtension, StreakInfo, WeekStart, Weekend, BOT_AUTHOR_PATTERN,
+	GENERATED_FILE_PATTERNS,
 };
 
-lazy_static! {
-	static ref AUTHOR_STR_RE: regex::Regex = regex::Regex::new("^(?:\"?([^\"]*)\"?\\s)?(?:<?(.+@[^>]+)?>?)$").unwrap();
+/// Returns the first day of the month of the given date
+fn first_day_of_month(date: NaiveDate) -> NaiveDate {
+	date.with_day0(0).unwrap()
+}
+
+/// Returns `value` as a percentage of `total`, or 0 if `total` is 0
+fn percentage(value: f64, total: f64) -> f64 {
+	if total == 0.0 {
+		return 0.0;
+	}
+	value * 100.0 / total
+}
+
+pub(crate) fn cmp_ignore_case(a: &str, b: &str) -> Ordering {
+	a.chars().flat_map(char::to_lowercase).cmp(b.chars().flat_map(char::to_lowercase))
+}
+
+/// Merge `other` into `map`, combining the values of the same author with `combine`.
+/// The merged author is keyed by the smallest identity, so that the result does not depend on the merge order
+fn merge_author_maps<V>(map: &mut HashMap<Author, V>, other: HashMap<Author, V>, combine: impl Fn(&mut V, V)) {
+	let mut keys = map.keys().map(|author| (author.identity_key(), author.clone())).collect::<HashMap<_, _>>();
+	for (author, value) in other {
+		match keys.get(&author.identity_key()).and_then(|key| map.remove_entry(key)) {
+			Some((key, mut current)) => {
+				combine(&mut current, value);
+				let key = if author.identity_cmp(&key) == Ordering::Less { author } else { key };
+				keys.insert(key.identity_key(), key.clone());
+				map.insert(key, current);
+			}
+			None => {
+				keys.insert(author.identity_key(), author.clone());
+				map.insert(author, value);
+			}
+		}
+	}
+}
+
+/// Merge two maps of per-author stats, like the ones of [CommitsPerWeekday] or [CommitsPerMonth]
+fn merge_nested_maps<K: Hash + Eq>(
+	map: &mut HashMap<K, HashMap<Author, SimpleStat>>,
+	other: HashMap<K, HashMap<Author, SimpleStat>>,
+) {
+	for (key, stats) in other {
+		merge_author_maps(map.entry(key).or_default(), stats, |current, stat| *current += stat);
+	}
 }
 
 // region Author
@@ -48,39 +98,80 @@ impl Author {
 	}
 }
 
+/// Parse an author in the `Name <email>` format.
+/// The email is the content of the last `<...>` segment, everything before it is the (trimmed) name, so the name
+/// can contain any character, including angle brackets: `Name <nick> <email>` is parsed as the name `Name <nick>`.
+/// The name can be surrounded by double quotes, which are removed. An empty `<>` or a string without the email
+/// segment (just `Name`) returns an author without email.
 impl<'a> TryFrom<&'a str> for Author {
-	type Error = anyhow::Error;
+	type Error = Error;
 
 	fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-		let find = AUTHOR_STR_RE
-			.captures(value)
-			.ok_or(anyhow!("failed to parse author string. Got {:}", value))?;
-
-		if find.len() == 3 {
-			let name = find
-				.get(1)
-				.ok_or(anyhow!("failed to extract author name from {:}", value))?
-				.as_str();
-
-			let email = find.get(2).map_or_else(|| None, |s| Some(s.as_str().to_string()));
-			Ok(Author {
-				name: name.to_string(),
-				email,
-			})
-		} else {
-			Err(anyhow!("invalid author mailmap"))
+		let value = value.trim();
+		if value.is_empty() {
+			return Err(Error::InvalidAuthor(value.to_string()));
 		}
+
+		let (name, email) = match value.strip_suffix('>') {
+			Some(rest) => {
+				let start = rest.rfind('<').ok_or_else(|| Error::InvalidAuthor(value.to_string()))?;
+				let email = rest[start + 1..].trim();
+				if email.contains('>') {
+					return Err(Error::InvalidAuthor(value.to_string()));
+				}
+				(&rest[..start], Some(email).filter(|email| !email.is_empty()))
+			}
+			None if value.contains(['<', '>']) => {
+				return Err(Error::InvalidAuthor(value.to_string()));
+			}
+			None => (value, None),
+		};
+
+		let name = name.trim();
+		let name = name.strip_prefix('"').and_then(|name| name.strip_suffix('"')).unwrap_or(name);
+		Ok(Author::new(name).with_email_opt(email))
 	}
 }
 
 impl TryFrom<String> for Author {
-	type Error = anyhow::Error;
+	type Error = Error;
 
 	fn try_from(value: String) -> Result<Self, Self::Error> {
 		value.as_str().try_into()
 	}
 }
 
+impl Author {
+	/// Returns true if the two authors share the same name or the same email (both case-insensitive).
+	/// This is the loose matching used to merge split identities in [CommitStatsExt::commits_per_author].
+	/// It is the same as `==`.
+	pub fn matches(&self, other: &Author) -> bool {
+		self == other
+	}
+
+	/// Returns true if the two authors share both the same name and the same email (both case-insensitive)
+	pub fn same_identity(&self, other: &Author) -> bool {
+		let email_match = match (&self.email, &other.email) {
+			(Some(e1), Some(e2)) => e1.eq_ignore_ascii_case(e2),
+			(None, None) => true,
+			_ => false,
+		};
+
+		self.name.eq_ignore_ascii_case(&other.name) && email_match
+	}
+
+	/// The lowercase name and email: equal for the authors matching [Author::same_identity], usable as a
+	/// hash key where `==` is too loose
+	pub(crate) fn identity_key(&self) -> (String, Option<String>) {
+		(self.name.to_lowercase(), self.email.as_ref().map(|email| email.to_lowercase()))
+	}
+
+	/// Compare two authors by their full identity (name, then email)
+	pub(crate) fn identity_cmp(&self, other: &Author) -> Ordering {
+		(&self.name, &self.email).cmp(&(&other.name, &other.email))
+	}
+}
+
 impl PartialEq for Author {
 	fn eq(&self, other: &Self) -> bool {
 		let email_match = match &self.email {
@@ -117,9 +208,23 @@ impl Display for CommitHash {
 	}
 }
 
-impl From<&str> for CommitHash {
-	fn from(value: &str) -> Self {
-		CommitHash(value.to_string())
+impl CommitHash {
+	/// Returns the first `n` characters of the hash (the whole hash if shorter)
+	pub fn short(&self, n: usize) -> &str {
+		&self.0[..n.min(self.0.len())]
+	}
+}
+
+/// Parse a full or abbreviated commit hash: from 4 to 40 hexadecimal digits.
+/// To expand an abbreviated hash, or any other reference, use [crate::Repo::resolve].
+impl TryFrom<&str> for CommitHash {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		if !(4..=40).contains(&value.len()) || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+			return Err(Error::InvalidCommitHash(value.to_string()));
+		}
+		Ok(CommitHash(value.to_string()))
 	}
 }
 
@@ -134,28 +239,111 @@ impl<'a> From<&'a CommitHash> for &'a str {
 // region CommitArgs
 
 impl CommitArgsBuilder {
+	/// Only the commits more recent than the given unix timestamp (in seconds), compared to the committer date.
+	/// The bound is inclusive: a commit made exactly at `value` is returned
 	pub fn since(mut self, value: i64) -> Self {
 		self.0.since = Some(value);
 		self
 	}
 
+	/// Only the commits older than the given unix timestamp (in seconds), compared to the committer date.
+	/// The bound is inclusive: a commit made exactly at `value` is returned
 	pub fn until(mut self, value: i64) -> Self {
 		self.0.until = Some(value);
 		self
 	}
 
+	/// Same as [CommitArgsBuilder::since], with a UTC datetime
+	pub fn since_datetime(self, value: DateTime<Utc>) -> Self {
+		self.since(value.timestamp())
+	}
+
+	/// Same as [CommitArgsBuilder::until], with a UTC datetime
+	pub fn until_datetime(self, value: DateTime<Utc>) -> Self {
+		self.until(value.timestamp())
+	}
+
+	/// Same as [CommitArgsBuilder::since], from the start of the given day (UTC)
+	pub fn since_date(self, value: NaiveDate) -> Self {
+		self.since_datetime(value.and_time(NaiveTime::MIN).and_utc())
+	}
+
+	/// Same as [CommitArgsBuilder::until], from the start of the given day (UTC)
+	pub fn until_date(self, value: NaiveDate) -> Self {
+		self.until_datetime(value.and_time(NaiveTime::MIN).and_utc())
+	}
+
+	/// Same as [CommitArgsBuilder::since], with a date passed as it is to git (`--since=<value>`), e.g.
+	/// `2 weeks ago`, `last month` or `yesterday`. Git resolves it relative to the current time.
+	/// Can't be combined with the other `since` setters.
+	pub fn since_relative(mut self, value: &str) -> Self {
+		self.0.since_relative = Some(value.to_string());
+		self
+	}
+
+	/// Same as [CommitArgsBuilder::until], with a date passed as it is to git (`--until=<value>`), see
+	/// [CommitArgsBuilder::since_relative]. Can't be combined with the other `until` setters.
+	pub fn until_relative(mut self, value: &str) -> Self {
+		self.0.until_relative = Some(value.to_string());
+		self
+	}
+
+	/// Only the commits with a committer date between the given unix timestamps (in seconds), both included.
+	/// Same as [CommitArgsBuilder::since] and [CommitArgsBuilder::until]: the range is passed to git
+	/// (`--since`/`--until`), which always compares them to the committer date.
+	pub fn commit_date_range(self, since: i64, until: i64) -> Self {
+		self.since(since).until(until)
+	}
+
+	/// Only the commits with an author date between the given unix timestamps (in seconds), both included.
+	/// git can't filter on the author date, so the commits are filtered after being read from `git log`: a
+	/// `max_count` is applied by git before this filter. A rebased or cherry-picked commit keeps its author date,
+	/// use this range to select the commits by the time they were written rather than the time they landed.
+	pub fn author_date_range(mut self, since: i64, until: i64) -> Self {
+		self.0.author_since = Some(since);
+		self.0.author_until = Some(until);
+		self
+	}
+
 	pub fn exclude_merges(mut self, value: bool) -> Self {
 		self.0.exclude_merges = value;
 		self
 	}
 
-	pub fn target_branch(mut self, value: &str) -> Self {
-		self.0.target_branch = Some(value.to_string());
+	/// Follow only the first parent of the merge commits (`--first-parent`), i.e. walk only the main line of
+	/// development of the target branch, ignoring the commits brought in by the merged branches.
+	/// The merge commits themselves are still listed: combined with [CommitArgsBuilder::exclude_merges] only the
+	/// non-merge commits made directly on the main line are returned.
+	/// With the default [HistoryScope::All] all the refs are walked, including the branches that have been merged,
+	/// so it's meant to be used together with [CommitArgsBuilder::target_branch] or [HistoryScope::Head].
+	pub fn first_parent(mut self, value: bool) -> Self {
+		self.0.first_parent = value;
+		self
+	}
+
+	/// Only the commits reachable from the given branch, same as [HistoryScope::Branch]
+	pub fn target_branch(self, value: &str) -> Self {
+		self.history_scope(HistoryScope::Branch(value.to_string()))
+	}
+
+	/// Which commits are walked: all the refs (the default), the current branch or a given branch.
+	/// Walking all the refs includes the abandoned and the remote branches, use [HistoryScope::Head] to only count
+	/// the commits of the mainline.
+	pub fn history_scope(mut self, value: HistoryScope) -> Self {
+		self.0.scope = value;
 		self
 	}
 
+	/// Only the commits of the given author, replacing the authors set by a previous call:
+	/// use [CommitArgsBuilder::authors] to include several authors
 	pub fn author(mut self, value: Author) -> Self {
-		self.0.author = Some(value);
+		self.0.authors = vec![value];
+		self
+	}
+
+	/// Only the commits of any of the given authors, replacing the authors set by a previous call
+	pub fn authors(mut self, value: Vec<Author>) -> Self {
+		self.0.authors = value;
 		self
 	}
 
@@ -164,7 +352,109 @@ impl CommitArgsBuilder {
 		self
 	}
 
-	pub fn build(self) -> anyhow::Result<CommitArgs> {
+	/// Exclude the commits of the authors matching any of the given Perl regular expressions, matched against the
+	/// beginning of the `Name <email>` identity of the author, like [CommitArgsBuilder::exclude_author].
+	/// All the exclusions are combined in a single `--author` filter, applied by git.
+	pub fn exclude_authors(mut self, value: Vec<String>) -> Self {
+		self.0.exclude_authors.extend(value);
+		self
+	}
+
+	/// Exclude the commits of bots and automation accounts, see [crate::BOT_AUTHOR_PATTERN] for the excluded
+	/// authors. Can be combined with [CommitArgsBuilder::exclude_author] and [CommitArgsBuilder::exclude_authors].
+	pub fn exclude_bots(mut self, value: bool) -> Self {
+		self.0.exclude_bots = value;
+		self
+	}
+
+	/// Limit the number of files collected for each commit by [crate::Repo::list_commit_details].
+	/// The files of huge commits (e.g. a vendored tree import) after the first `value` are skipped and the
+	/// commit is marked as `truncated`. The commit stats are still complete.
+	pub fn max_stat_files(mut self, value: u32) -> Self {
+		self.0.max_stat_files = Some(value);
+		self
+	}
+
+	/// Map the authors through the repository `.mailmap` (passing `--use-mailmap` to `git log`),
+	/// regardless of the `log.mailmap` git configuration, so that `author` and `exclude_author` match the
+	/// canonical identities.
+	///
+	/// The author name and email in the commit stats are always the mailmap'ed ones, so a `.mailmap`
+	/// is the preferred way to merge split identities, rather than relying on the loose [Author::matches].
+	pub fn use_mailmap(mut self, value: bool) -> Self {
+		self.0.use_mailmap = value;
+		self
+	}
+
+	/// Only the commits changing the number of occurrences of `value` (`git log -S<value>`), e.g. to find the
+	/// commits introducing or removing a function.
+	/// Git has to diff every commit to find the matches, so this can be slow on big repositories.
+	pub fn pickaxe(mut self, value: &str) -> Self {
+		self.0.pickaxe = Some(value.to_string());
+		self
+	}
+
+	/// Only the commits whose diff adds or removes a line matching the `value` regex (`git log -G<value>`).
+	/// Like [CommitArgsBuilder::pickaxe], this can be slow on big repositories.
+	pub fn pickaxe_regex(mut self, value: &str) -> Self {
+		self.0.pickaxe_regex = Some(value.to_string());
+		self
+	}
+
+	/// Limit the number of commits (`git log --max-count`).
+	/// The limit is applied before the commits are reversed, so [crate::Repo::list_commits] returns the `value`
+	/// newest commits, in chronological order.
+	pub fn max_count(mut self, value: usize) -> Self {
+		self.0.max_count = Some(value);
+		self
+	}
+
+	/// Exclude the commits whose message matches the given regular expression (e.g. `^chore:` or
+	/// `Merge pull request`), using `--grep=<value> --invert-grep`. Can be called multiple times: the commits
+	/// matching any of the patterns are excluded.
+	/// git inverts all the `--grep` patterns, so a positive message filter can't be combined with this one.
+	/// Note that the patterns are Perl regular expressions when some authors are excluded (e.g. with
+	/// [CommitArgsBuilder::exclude_author]).
+	pub fn exclude_message(mut self, value: &str) -> Self {
+		self.0.exclude_messages.push(value.to_string());
+		self
+	}
+
+	/// Exclude the given path (e.g. a generated directory like `vendor/`) from the commits and their stats,
+	/// using the `:(exclude)` pathspec. Can be called multiple times.
+	/// The commits touching only excluded paths are not returned.
+	pub fn exclude_path(mut self, value: &str) -> Self {
+		self.0.exclude_paths.push(value.to_string());
+		self
+	}
+
+	/// Exclude the generated files from the commits and their stats, like [CommitArgsBuilder::exclude_path]:
+	/// - the files matching [crate::GENERATED_FILE_PATTERNS] (e.g. `Cargo.lock` or `*.min.js`), in any directory
+	/// - the files marked as `linguist-generated` or `-diff` (e.g. `binary`) in the `.gitattributes`
+	///
+	/// The attributes are read from the working tree, so they apply to the whole history.
+	pub fn exclude_generated(mut self, value: bool) -> Self {
+		self.0.exclude_generated = value;
+		self
+	}
+
+	/// Only the commits renaming or moving a file (`git log --diff-filter=R`), e.g. to build a timeline of the file
+	/// movements of a refactoring. As the diff is filtered too, the stats of the commits only count the renamed files.
+	pub fn renames_only(mut self, value: bool) -> Self {
+		self.0.renames_only = value;
+		self
+	}
+
+	/// The similarity index, in percent, for a deleted and an added file to be detected as a rename
+	/// (`--find-renames=<value>%`). Git's default is 50%: a file is renamed if at least half of it is unchanged.
+	/// Must be between 0 and 100. Without it, the renames of the commit details are always detected with the
+	/// default threshold, regardless of the `diff.renames` git configuration.
+	pub fn rename_threshold(mut self, value: u8) -> Self {
+		self.0.rename_threshold = Some(value);
+		self
+	}
+
+	pub fn build(self) -> Result<CommitArgs, Error> {
 		self.0.validate()?;
 		Ok(self.0)
 	}
@@ -180,10 +470,14 @@ impl CommitArgs {
 	///
 	///
 	/// use gitstats::{Author, CommitArgs};
+	/// # #[cfg(feature = "repo")]
 	/// use gitstats::Repo;
 	///
 	///
 	///
+	/// # #[cfg(not(feature = "repo"))]
+	/// # fn main() {}
+	/// # #[cfg(feature = "repo")]
 	/// pub fn main() {
 	/// let repo = Repo::new("/custom/path");
 	/// 	let args = CommitArgs::builder()
@@ -194,7 +488,7 @@ impl CommitArgs {
 	/// 		.target_branch("develop")
 	/// 		.build().unwrap();
 	/// 	if let Ok(result) = repo.list_commits(args) {
-	///         println!("got commits: {}", result);
+	///         println!("got commits: {:?}", result);
 	///     }
 	/// }
 	/// ```
@@ -202,20 +496,79 @@ impl CommitArgs {
 		CommitArgsBuilder(Default::default())
 	}
 
-	pub(crate) fn validate(&self) -> anyhow::Result<()> {
-		if self.author.is_some() && self.exclude_author.is_some() {
-			return Err(anyhow!("cannot specify both author and exclude_author"));
+	pub(crate) fn validate(&self) -> Result<(), Error> {
+		if !self.authors.is_empty() && !self.excluded_authors().is_empty() {
+			return Err(Error::InvalidArgs("cannot specify both author and exclude_author".to_string()));
+		}
+
+		if self.pickaxe.is_some() && self.pickaxe_regex.is_some() {
+			return Err(Error::InvalidArgs("cannot specify both pickaxe and pickaxe_regex".to_string()));
 		}
 
 		if let Some(since) = self.since {
-			DateTime::from_timestamp(since, 0).context("invalid datetime specified for since")?;
+			DateTime::from_timestamp(since, 0)
+				.ok_or_else(|| Error::InvalidArgs("invalid datetime specified for since".to_string()))?;
 		}
 
 		if let Some(until) = self.until {
-			DateTime::from_timestamp(until, 0).context("invalid datetime specified for until")?;
+			DateTime::from_timestamp(until, 0)
+				.ok_or_else(|| Error::InvalidArgs("invalid datetime specified for until".to_string()))?;
+		}
+
+		if self.since.is_some() && self.since_relative.is_some() {
+			return Err(Error::InvalidArgs("cannot specify both since and since_relative".to_string()));
+		}
+
+		if self.until.is_some() && self.until_relative.is_some() {
+			return Err(Error::InvalidArgs("cannot specify both until and until_relative".to_string()));
+		}
+
+		if let (Some(since), Some(until)) = (self.author_since, self.author_until) {
+			if since > until {
+				return Err(Error::InvalidArgs("the author date range ends before it starts".to_string()));
+			}
+		}
+
+		if self.rename_threshold.is_some_and(|threshold| threshold > 100) {
+			return Err(Error::InvalidArgs("the rename threshold must be between 0 and 100".to_string()));
+		}
+
+		Ok(())
+	}
+
+	/// The patterns of the excluded authors: `exclude_author`, `exclude_authors` and the bots
+	fn excluded_authors(&self) -> Vec<&str> {
+		let mut patterns: Vec<&str> =
+			self.exclude_author.iter().chain(&self.exclude_authors).map(String::as_str).collect();
+		if self.exclude_bots {
+			patterns.push(BOT_AUTHOR_PATTERN);
+		}
+		patterns
+	}
+
+	/// The `:(exclude)` pathspecs of the excluded paths and of the generated files
+	pub(crate) fn excluded_pathspecs(&self) -> Vec<String> {
+		let mut pathspecs = self.exclude_paths.iter().map(|path| format!(":(exclude){path}")).collect::<Vec<_>>();
+		if self.exclude_generated {
+			pathspecs.extend(GENERATED_FILE_PATTERNS.iter().map(|pattern| format!(":(exclude,glob)**/{pattern}")));
+			for attr in ["linguist-generated", "linguist-generated=true", "-diff"] {
+				pathspecs.push(format!(":(exclude,attr:{attr})"));
+			}
 		}
+		pathspecs
+	}
 
-		return Ok(());
+	/// True if there's a filter on the author date, see [CommitArgsBuilder::author_date_range]
+	#[cfg(feature = "repo")]
+	pub(crate) fn has_author_date_range(&self) -> bool {
+		self.author_since.is_some() || self.author_until.is_some()
+	}
+
+	/// True if the given author timestamp is inside the author date range (always true without a range)
+	#[cfg(feature = "repo")]
+	pub(crate) fn matches_author_date(&self, timestamp: i64) -> bool {
+		self.author_since.is_none_or(|since| timestamp >= since)
+			&& self.author_until.is_none_or(|until| timestamp <= until)
 	}
 }
 
@@ -224,27 +577,43 @@ impl IntoIterator for CommitArgs {
 	type IntoIter = std::vec::IntoIter<Self::Item>;
 
 	fn into_iter(self) -> Self::IntoIter {
+		self.to_git_args().into_iter()
+	}
+}
+
+impl CommitArgs {
+	/// Returns the exact `git log` arguments of these options, e.g. to print the command that will run.
+	/// Unlike the `Display`, which is meant to be read by humans, nothing is reformatted or omitted.
+	/// Same as iterating the arguments, without consuming them.
+	pub fn to_git_args(&self) -> Vec<OsString> {
 		let mut args: Vec<OsString> = vec![];
 
-		if let Some(target_branch) = self.target_branch {
-			args.push(target_branch.into());
-		} else {
-			args.push("--all".into());
+		match &self.scope {
+			HistoryScope::All => args.push("--all".into()),
+			HistoryScope::Head => args.push("HEAD".into()),
+			HistoryScope::Branch(branch) => args.push(branch.into()),
 		}
 
-		args.push("--pretty=%H".into());
-
 		if let Some(since) = self.since {
 			let datetime = DateTime::from_timestamp(since, 0).unwrap();
-			args.push(format!("--since={:}", datetime.format("%Y-%m-%d").to_string()).into());
+			args.push(format!("--since={:}", datetime.format("%Y-%m-%dT%H:%M:%SZ")).into());
 		}
 
 		if let Some(until) = self.until {
 			let datetime = DateTime::from_timestamp(until, 0).unwrap();
-			args.push(format!("--until={:}", datetime.format("%Y-%m-%d").to_string()).into());
+			args.push(format!("--until={:}", datetime.format("%Y-%m-%dT%H:%M:%SZ")).into());
+		}
+
+		if let Some(since) = self.since_relative.as_ref() {
+			args.push(format!("--since={since}").into());
+		}
+
+		if let Some(until) = self.until_relative.as_ref() {
+			args.push(format!("--until={until}").into());
 		}
 
-		if let Some(author) = self.author.as_ref() {
+		// git matches the commits of any of the authors
+		for author in self.authors.iter() {
 			args.push(format!("--author={:}", author.name).into());
 		}
 
@@ -252,41 +621,159 @@ impl IntoIterator for CommitArgs {
 			args.push("--no-merges".into());
 		}
 
-		if let Some(exclude_author) = self.exclude_author.as_ref() {
+		if self.first_parent {
+			args.push("--first-parent".into());
+		}
+
+		if self.use_mailmap {
+			args.push("--use-mailmap".into());
+		}
+
+		if let Some(max_count) = self.max_count {
+			args.push(format!("--max-count={max_count}").into());
+		}
+
+		if let Some(pickaxe) = self.pickaxe.as_ref() {
+			args.push(format!("-S{pickaxe}").into());
+		}
+
+		if let Some(pickaxe_regex) = self.pickaxe_regex.as_ref() {
+			args.push(format!("-G{pickaxe_regex}").into());
+		}
+
+		let excluded_authors = self.excluded_authors();
+		if let [exclude_author] = excluded_authors.as_slice() {
 			args.push("--perl-regexp".into());
 			args.push(format!("--author=^((?!{:}).*)$", exclude_author).into());
+		} else if !excluded_authors.is_empty() {
+			// a single negative lookahead, as git matches the commits of any of the `--author` patterns
+			let patterns = excluded_authors.iter().map(|pattern| format!("(?:{pattern})")).collect::<Vec<_>>();
+			args.push("--perl-regexp".into());
+			args.push(format!("--author=^((?!{:}).*)$", patterns.join("|")).into());
+		}
+
+		match self.rename_threshold {
+			Some(threshold) => args.push(format!("--find-renames={threshold}%").into()),
+			None if self.renames_only => args.push("--find-renames".into()),
+			None => {}
+		}
+
+		if self.renames_only {
+			args.push("--diff-filter=R".into());
 		}
 
-		args.into_iter()
+		if !self.exclude_messages.is_empty() {
+			for pattern in self.exclude_messages.iter() {
+				args.push(format!("--grep={pattern}").into());
+			}
+			args.push("--invert-grep".into());
+		}
+
+		// the pathspecs must be the last arguments
+		let pathspecs = self.excluded_pathspecs();
+		if !pathspecs.is_empty() {
+			args.push("--".into());
+			args.extend(pathspecs.into_iter().map(OsString::from));
+		}
+
+		args
 	}
 }
 
 impl Display for CommitArgs {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		let mut s = vec![];
-		if let Some(author) = self.author.as_ref() {
+		for author in self.authors.iter() {
 			s.push(format!("author:{}", author));
 		}
 		if let Some(exclude_author) = self.exclude_author.as_ref() {
 			s.push(format!("exclude author:{}", exclude_author));
 		}
+		for exclude_author in self.exclude_authors.iter() {
+			s.push(format!("exclude author:{}", exclude_author));
+		}
+		if self.exclude_bots {
+			s.push("exclude_bots:true".to_string());
+		}
 
 		if self.exclude_merges {
 			s.push("exclude_merges:true".to_string());
 		}
 
-		if let Some(value) = self.target_branch.as_ref() {
-			s.push(format!("target_branch:{}", value));
+		if self.first_parent {
+			s.push("first_parent:true".to_string());
+		}
+
+		match &self.scope {
+			HistoryScope::All => {}
+			HistoryScope::Head => s.push("scope:HEAD".to_string()),
+			HistoryScope::Branch(branch) => s.push(format!("target_branch:{}", branch)),
+		}
+
+		if self.use_mailmap {
+			s.push("use_mailmap:true".to_string());
+		}
+
+		if let Some(value) = self.max_stat_files.as_ref() {
+			s.push(format!("max_stat_files:{}", value));
+		}
+
+		if let Some(value) = self.max_count.as_ref() {
+			s.push(format!("max_count:{}", value));
+		}
+
+		if let Some(value) = self.pickaxe.as_ref() {
+			s.push(format!("pickaxe:{}", value));
+		}
+
+		if let Some(value) = self.pickaxe_regex.as_ref() {
+			s.push(format!("pickaxe_regex:{}", value));
+		}
+
+		for path in self.exclude_paths.iter() {
+			s.push(format!("exclude_path:{}", path));
+		}
+
+		if self.exclude_generated {
+			s.push("exclude_generated:true".to_string());
+		}
+
+		for pattern in self.exclude_messages.iter() {
+			s.push(format!("exclude_message:{}", pattern));
+		}
+
+		if self.renames_only {
+			s.push("renames_only:true".to_string());
+		}
+
+		if let Some(value) = self.rename_threshold.as_ref() {
+			s.push(format!("rename_threshold:{}%", value));
 		}
 
 		if let Some(value) = self.since.as_ref() {
 			let datetime = DateTime::from_timestamp(*value, 0).unwrap();
-			s.push(format!("since={:}", datetime.format("%Y-%m-%d").to_string()).into());
+			s.push(format!("commit_since:{:}", datetime.format("%Y-%m-%d")));
 		}
 
 		if let Some(value) = self.until.as_ref() {
 			let datetime = DateTime::from_timestamp(*value, 0).unwrap();
-			s.push(format!("until:{:}", datetime.format("%Y-%m-%d").to_string()).into());
+			s.push(format!("commit_until:{:}", datetime.format("%Y-%m-%d")));
+		}
+
+		if let Some(value) = self.since_relative.as_ref() {
+			s.push(format!("commit_since:{}", value));
+		}
+
+		if let Some(value) = self.until_relative.as_ref() {
+			s.push(format!("commit_until:{}", value));
+		}
+
+		if let Some(value) = self.author_since.and_then(|value| DateTime::from_timestamp(value, 0)) {
+			s.push(format!("author_since:{:}", value.format("%Y-%m-%d")));
+		}
+
+		if let Some(value) = self.author_until.and_then(|value| DateTime::from_timestamp(value, 0)) {
+			s.push(format!("author_until:{:}", value.format("%Y-%m-%d")));
 		}
 
 		write!(f, "{}", s.join(", "))
@@ -297,6 +784,28 @@ impl Display for CommitArgs {
 
 // region CommitStats
 
+impl CommitStats {
+	/// Lines added minus lines deleted, negative if more lines were deleted than added
+	pub fn net_lines(&self) -> i64 {
+		let net = self.lines_added as i128 - self.lines_deleted as i128;
+		net.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+	}
+
+	/// Lines added plus lines deleted (saturating at `u64::MAX`)
+	pub fn total_lines(&self) -> u64 {
+		self.lines_added.saturating_add(self.lines_deleted)
+	}
+
+	/// Lines deleted per line added, 0.0 if no lines were added
+	pub fn churn_ratio(&self) -> f64 {
+		if self.lines_added == 0 {
+			0.0
+		} else {
+			self.lines_deleted as f64 / self.lines_added as f64
+		}
+	}
+}
+
 impl std::ops::Add for CommitStats {
 	type Output = CommitStats;
 
@@ -317,6 +826,25 @@ impl std::ops::AddAssign for CommitStats {
 	}
 }
 
+/// Saturating subtraction, e.g. to compute the change between two periods: each counter is clamped at zero
+impl std::ops::Sub for CommitStats {
+	type Output = CommitStats;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		CommitStats {
+			files_changed: self.files_changed.saturating_sub(rhs.files_changed),
+			lines_added: self.lines_added.saturating_sub(rhs.lines_added),
+			lines_deleted: self.lines_deleted.saturating_sub(rhs.lines_deleted),
+		}
+	}
+}
+
+impl std::ops::SubAssign for CommitStats {
+	fn sub_assign(&mut self, rhs: Self) {
+		*self = *self - rhs;
+	}
+}
+
 impl Display for CommitStats {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		write!(
@@ -331,16 +859,61 @@ impl Display for CommitStats {
 
 // region GlobalStat
 
+impl GlobalStat {
+	/// Returns the author email, or an empty string if the author has no email
+	pub fn email(&self) -> &str {
+		self.author.email.as_deref().unwrap_or("")
+	}
+
+	/// Returns the fraction (0 to 1) of the `total` commits authored by this author.
+	/// Returns 0 if `total` has no commits.
+	pub fn share_of(&self, total: &SimpleStat) -> f64 {
+		if total.commits_count == 0 {
+			return 0.0;
+		}
+		self.commits_count as f64 / total.commits_count as f64
+	}
+
+	/// Returns the fraction (0 to 1) of the `total` churn (lines added + lines deleted) authored by this author.
+	/// Returns 0 if `total` has no churn.
+	pub fn churn_share_of(&self, total: &SimpleStat) -> f64 {
+		let total_churn = total.stats.lines_added as f64 + total.stats.lines_deleted as f64;
+		if total_churn == 0.0 {
+			return 0.0;
+		}
+		(self.stats.lines_added as f64 + self.stats.lines_deleted as f64) / total_churn
+	}
+
+	/// Returns the average size of the commits of the author, in lines added plus lines deleted.
+	/// Returns 0 if the author has no commits. See [CommitsPerAuthor::median_lines_per_commit] for a measure
+	/// less skewed by a few huge commits.
+	pub fn avg_lines_per_commit(&self) -> f64 {
+		if self.commits_count == 0 {
+			return 0.0;
+		}
+		self.stats.total_lines() as f64 / self.commits_count as f64
+	}
+}
+
 impl Display for GlobalStat {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		write!(
 			f,
-			"author: {}, total commits: {}, {}",
-			self.author, self.commits_count, self.stats
+			"author: {}, total commits: {}, {}, first commit: {}, last commit: {}",
+			self.author,
+			self.commits_count,
+			self.stats,
+			format_date(self.first_commit),
+			format_date(self.last_commit)
 		)
 	}
 }
 
+/// Format a timestamp as `%Y-%m-%d`, in UTC
+fn format_date(timestamp: i64) -> String {
+	DateTime::from_timestamp(timestamp, 0).map_or_else(|| timestamp.to_string(), |date| date.format("%Y-%m-%d").to_string())
+}
+
 // endregion GlobalStat
 
 // region SimpleStat
@@ -349,6 +922,21 @@ impl SimpleStat {
 	pub fn new() -> Self {
 		SimpleStat::default()
 	}
+
+	/// See [CommitStats::net_lines]
+	pub fn net_lines(&self) -> i64 {
+		self.stats.net_lines()
+	}
+
+	/// See [CommitStats::total_lines]
+	pub fn total_lines(&self) -> u64 {
+		self.stats.total_lines()
+	}
+
+	/// See [CommitStats::churn_ratio]
+	pub fn churn_ratio(&self) -> f64 {
+		self.stats.churn_ratio()
+	}
 }
 
 impl Display for SimpleStat {
@@ -375,12 +963,38 @@ impl std::ops::AddAssign for SimpleStat {
 	}
 }
 
+/// Saturating subtraction, each counter is clamped at zero
+impl std::ops::Sub for SimpleStat {
+	type Output = SimpleStat;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		SimpleStat {
+			commits_count: self.commits_count.saturating_sub(rhs.commits_count),
+			stats: self.stats - rhs.stats,
+		}
+	}
+}
+
+impl std::ops::SubAssign for SimpleStat {
+	fn sub_assign(&mut self, rhs: Self) {
+		self.commits_count = self.commits_count.saturating_sub(rhs.commits_count);
+		self.stats -= rhs.stats;
+	}
+}
+
 impl From<CommitDetail> for SimpleStat {
 	fn from(value: CommitDetail) -> Self {
 		value.stats.into()
 	}
 }
 
+/// The grand total of the commits: see also [CommitStatsExt::total]
+impl FromIterator<CommitDetail> for SimpleStat {
+	fn from_iter<T: IntoIterator<Item = CommitDetail>>(iter: T) -> Self {
+		iter.into_iter().map(SimpleStat::from).fold(SimpleStat::new(), |acc, item| acc + item)
+	}
+}
+
 impl From<CommitStats> for SimpleStat {
 	fn from(value: CommitStats) -> Self {
 		SimpleStat {
@@ -400,6 +1014,7 @@ impl From<CommitDetail> for MinimalCommitDetail {
 			hash: value.hash,
 			author_timestamp: value.author_timestamp,
 			stats: value.stats,
+			files: value.files.into_iter().map(|file| file.path).collect(),
 		}
 	}
 }
@@ -414,22 +1029,51 @@ impl Display for MinimalCommitDetail {
 
 // region SortStatsBy
 
-impl Default for SortStatsBy {
-	fn default() -> Self {
-		SortStatsBy::Commits
+impl SortStatsBy {
+	/// The order used by [CommitsPerAuthor::global_stats]
+	pub fn default_order(&self) -> SortOrder {
+		match self {
+			SortStatsBy::Name | SortStatsBy::Email => SortOrder::Ascending,
+			_ => SortOrder::Descending,
+		}
 	}
 }
 
 // endregion SortStatsBy
 
+// region FileStat
+
+impl FileStat {
+	/// Returns the file extension, or an empty string if the file has no extension
+	pub fn extension(&self) -> &str {
+		Path::new(&self.path).extension().and_then(|e| e.to_str()).unwrap_or("")
+	}
+}
+
+impl From<&FileStat> for CommitStats {
+	fn from(value: &FileStat) -> Self {
+		CommitStats {
+			files_changed: 1,
+			lines_added: value.lines_added,
+			lines_deleted: value.lines_deleted,
+		}
+	}
+}
+
+// endregion FileStat
+
 // region CommitDetail
 
 impl CommitDetail {
 	pub fn get_author_datetime(&self) -> DateTime<Utc> {
-		let naive = NaiveDateTime::from_timestamp_opt(self.author_timestamp, 0).unwrap();
-		DateTime::from_naive_utc_and_offset(naive, Utc)
+		DateTime::from_timestamp(self.author_timestamp, 0).unwrap()
 	}
-}
+
+	/// Same as [CommitDetail::get_author_datetime], in the timezone with the given `offset` from UTC
+	pub fn get_author_datetime_in(&self, offset: FixedOffset) -> DateTime<FixedOffset> {
+		self.get_author_datetime().with_timezone(&offset)
+	}
+}
 
 impl Display for CommitDetail {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -448,168 +1092,337 @@ impl Display for CommitDetail {
 
 // region CommitStatsExt
 
-impl<'a> CommitStatsExt for Vec<CommitDetail> {
-	fn commits_per_author(&self) -> CommitsPerAuthor {
-		let mut hashmap: HashMap<Author, Vec<MinimalCommitDetail>> = HashMap::new();
+/// The case-insensitive identity of an author
+fn identity_key(author: &Author) -> (String, Option<String>) {
+	(author.name.to_ascii_lowercase(), author.email.as_ref().map(|email| email.to_ascii_lowercase()))
+}
 
-		let mut cloned = self.to_vec();
+/// Assign the authors of the commits to the groups of [CommitStatsExt::commits_per_author], returning the group
+/// of each identity (see [identity_key]). The first author with a given name or email takes all the commits left
+/// matching it (see [Author::matches]): the commits of each name and email are indexed, so that every list is
+/// consumed once instead of scanning all the commits again for each author.
+fn author_groups(commits: &[CommitDetail]) -> HashMap<(String, Option<String>), usize> {
+	let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+	let mut by_email: HashMap<String, Vec<usize>> = HashMap::new();
+	for (index, commit) in commits.iter().enumerate() {
+		let (name, email) = identity_key(&commit.author);
+		by_name.entry(name).or_default().push(index);
+		if let Some(email) = email {
+			by_email.entry(email).or_default().push(index);
+		}
+	}
 
-		while !cloned.is_empty() {
-			let commit = cloned.remove(0);
-			let author = commit.author.to_owned();
-			let minimal_commit: MinimalCommitDetail = commit.into();
-			let mut vec: Vec<MinimalCommitDetail> = Vec::new();
-			let mut index = Some(0);
+	// all the commits of an identity are always in the same group
+	let mut groups: HashMap<(String, Option<String>), usize> = HashMap::new();
+	for (group, commit) in commits.iter().enumerate() {
+		let identity = identity_key(&commit.author);
+		if groups.contains_key(&identity) {
+			continue;
+		}
+		let mut indexes = by_name.remove(&identity.0).unwrap_or_default();
+		if let Some(email) = &identity.1 {
+			indexes.extend(by_email.remove(email).unwrap_or_default());
+		}
+		for index in indexes {
+			groups.entry(identity_key(&commits[index].author)).or_insert(group);
+		}
+	}
+	groups
+}
 
-			while index.is_some() {
-				index = cloned.iter().position(|c| {
-					let ca = &c.author;
-					author.eq(ca)
-				});
+impl CommitStatsExt for Vec<CommitDetail> {
+	fn commits_per_author(&self) -> CommitsPerAuthor {
+		let groups = author_groups(self);
+		self.commits_per_author_by(|author| groups[&identity_key(author)].to_string())
+	}
 
-				if let Some(index) = index {
-					let commit2 = cloned.remove(index);
-					vec.push(commit2.into());
-				}
+	fn commits_per_author_by<F>(&self, key_fn: F) -> CommitsPerAuthor
+	where
+		F: Fn(&Author) -> String,
+	{
+		let mut groups: HashMap<String, (Author, Vec<MinimalCommitDetail>)> = HashMap::new();
+		for commit in self.iter() {
+			let (representative, commits) = groups
+				.entry(key_fn(&commit.author))
+				.or_insert_with(|| (commit.author.to_owned(), Vec::new()));
+			if commit.author.identity_cmp(representative) == Ordering::Less {
+				*representative = commit.author.to_owned();
 			}
+			commits.push(commit.to_owned().into());
+		}
+		CommitsPerAuthor(groups.into_values().collect())
+	}
 
-			vec.insert(0, minimal_commit);
-			hashmap.insert(author.to_owned(), vec);
-		}
-		CommitsPerAuthor(hashmap)
-	}
-
-	fn commits_per_month(mut self) -> CommitsPerMonth {
-		let mut result: HashMap<String, HashMap<Author, SimpleStat>> = HashMap::new();
-		if self.len() > 1 {
-			let last = self.last().unwrap();
-			let first = self.first().unwrap();
-			let last_date = last.get_author_datetime();
-			let mut first_date = first
-				.get_author_datetime()
-				.with_day(last_date.day())
-				.unwrap()
-				.with_hour(0)
-				.unwrap()
-				.with_minute(0)
-				.unwrap()
-				.with_second(0)
-				.unwrap()
-				.with_nanosecond(0)
-				.unwrap();
-
-			loop {
-				let date_key = first_date.with_day0(0).unwrap().format("%Y-%m").to_string();
-				let mut current_map: HashMap<Author, SimpleStat> = HashMap::new();
+	fn commits_per_author_with(&self, mode: CoAuthorMode) -> CommitsPerAuthor {
+		if mode == CoAuthorMode::Ignore {
+			return self.commits_per_author();
+		}
 
-				if self.is_empty() {
-					break;
+		let mut credited: Vec<CommitDetail> = Vec::with_capacity(self.len());
+		for commit in self.iter() {
+			let mut participants = vec![commit.author.to_owned()];
+			for co_author in commit.co_authors.iter() {
+				if !participants.contains(co_author) {
+					participants.push(co_author.to_owned());
 				}
+			}
 
-				loop {
-					if self.is_empty() {
-						break;
+			let count = participants.len() as u64;
+			let share = match mode {
+				CoAuthorMode::Split => CommitStats {
+					files_changed: commit.stats.files_changed / count,
+					lines_added: commit.stats.lines_added / count,
+					lines_deleted: commit.stats.lines_deleted / count,
+				},
+				_ => commit.stats,
+			};
+
+			for (index, author) in participants.into_iter().enumerate() {
+				let stats = if index == 0 && mode == CoAuthorMode::Split {
+					CommitStats {
+						files_changed: commit.stats.files_changed - share.files_changed * (count - 1),
+						lines_added: commit.stats.lines_added - share.lines_added * (count - 1),
+						lines_deleted: commit.stats.lines_deleted - share.lines_deleted * (count - 1),
 					}
+				} else {
+					share
+				};
 
-					let commit = self.get(0).unwrap();
-					let commit_datetime = commit.get_author_datetime();
-					if commit_datetime.year() <= first_date.year() && commit_datetime.month() <= first_date.month() {
-						let removed = self.remove(0);
-						let author = removed.author.to_owned();
-						if current_map.contains_key(&author) {
-							*current_map.get_mut(&author).unwrap() += removed.into();
-						} else {
-							current_map.insert(author, removed.into());
-						}
-					} else {
-						break;
-					}
-				}
-				result.insert(date_key, current_map);
+				credited.push(CommitDetail {
+					author,
+					stats,
+					..commit.to_owned()
+				});
+			}
+		}
+		credited.commits_per_author()
+	}
 
-				first_date = first_date.checked_add_months(Months::new(1)).unwrap();
-				if first_date > last_date {
-					break;
-				}
+	fn commits_per_month(self) -> CommitsPerMonth {
+		self.commits_per_month_in(Utc.fix())
+	}
+
+	fn commits_per_month_in(self, offset: FixedOffset) -> CommitsPerMonth {
+		let mut result = CommitsPerMonth::default();
+		let first = self.iter().min_by_key(|commit| commit.author_timestamp);
+		let last = self.iter().max_by_key(|commit| commit.author_timestamp);
+		if let (Some(first), Some(last)) = (first, last) {
+			// every month of the range is a key, even without commits
+			let last_month = first_day_of_month(last.get_author_datetime_in(offset).date_naive());
+			let mut current_month = first_day_of_month(first.get_author_datetime_in(offset).date_naive());
+			while current_month <= last_month {
+				result.0.insert(current_month.format("%Y-%m").to_string(), HashMap::new());
+				current_month = current_month.checked_add_months(Months::new(1)).unwrap();
 			}
 		}
-		CommitsPerMonth(result)
+		self.iter().for_each(|commit| result.accumulate_in(commit, offset));
+		result
 	}
 
-	fn commits_per_weekday(mut self) -> CommitsPerWeekday {
-		let mut final_map: HashMap<u8, HashMap<Author, SimpleStat>> = HashMap::from([
-			(Weekday::Mon.num_days_from_monday() as u8, HashMap::new()),
-			(Weekday::Tue.num_days_from_monday() as u8, HashMap::new()),
-			(Weekday::Wed.num_days_from_monday() as u8, HashMap::new()),
-			(Weekday::Thu.num_days_from_monday() as u8, HashMap::new()),
-			(Weekday::Fri.num_days_from_monday() as u8, HashMap::new()),
-			(Weekday::Sat.num_days_from_monday() as u8, HashMap::new()),
-			(Weekday::Sun.num_days_from_monday() as u8, HashMap::new()),
-		]);
+	fn commits_per_week(self) -> CommitsPerWeek {
+		self.commits_per_week_in(Utc.fix())
+	}
 
-		for commit in self.iter_mut() {
-			let author = commit.author.to_owned();
-			let datetime = commit.get_author_datetime();
-			let weekday = datetime.weekday().num_days_from_monday() as u8;
-			if !final_map.get(&weekday).unwrap().contains_key(&author) {
-				final_map.get_mut(&weekday).unwrap().insert(author.clone(), SimpleStat::new());
+	fn commits_per_week_in(self, offset: FixedOffset) -> CommitsPerWeek {
+		let mut result = CommitsPerWeek::default();
+		let first = self.iter().min_by_key(|commit| commit.author_timestamp);
+		let last = self.iter().max_by_key(|commit| commit.author_timestamp);
+		if let (Some(first), Some(last)) = (first, last) {
+			// every week of the range is a key, even without commits
+			let last_day = last.get_author_datetime_in(offset).date_naive();
+			let first_day = first.get_author_datetime_in(offset).date_naive();
+			let mut current_week = first_day - Days::new(first_day.weekday().num_days_from_monday().into());
+			while current_week <= last_day {
+				result.0.insert(week_key(current_week), HashMap::new());
+				current_week = current_week + Days::new(7);
 			}
-			*final_map.get_mut(&weekday).unwrap().get_mut(&author).unwrap() += commit.to_owned().into();
 		}
-		CommitsPerWeekday(final_map)
+		self.iter().for_each(|commit| result.accumulate_in(commit, offset));
+		result
+	}
+
+	fn commits_per_weekday(self) -> CommitsPerWeekday {
+		self.commits_per_weekday_in(Utc.fix())
+	}
+
+	fn commits_per_weekday_in(self, offset: FixedOffset) -> CommitsPerWeekday {
+		let mut result = CommitsPerWeekday::default();
+		self.iter().for_each(|commit| result.accumulate_in(commit, offset));
+		result
 	}
 
 	fn commits_per_day_hour(self) -> CommitsPerDayHour {
-		let mut final_map: HashMap<u32, HashMap<Author, SimpleStat>> = HashMap::new();
-		for i in 0..24 {
-			final_map.insert(i, HashMap::new());
-		}
+		self.commits_per_day_hour_in(Utc.fix())
+	}
 
-		for commit in self.into_iter() {
-			let author = commit.author.to_owned();
-			let datetime = commit.get_author_datetime();
-			let hour = datetime.hour();
-			if !final_map.get(&hour).unwrap().contains_key(&author) {
-				final_map.get_mut(&hour).unwrap().insert(author, commit.into());
+	fn commits_per_day_hour_in(self, offset: FixedOffset) -> CommitsPerDayHour {
+		let mut result = CommitsPerDayHour::default();
+		self.iter().for_each(|commit| result.accumulate_in(commit, offset));
+		result
+	}
+
+	fn commits_heatmap(self) -> CommitsHeatMap {
+		self.commits_heatmap_in(Utc.fix())
+	}
+
+	fn commits_heatmap_in(self, offset: FixedOffset) -> CommitsHeatMap {
+		let mut result = CommitsHeatMap::default();
+		self.iter().for_each(|commit| result.accumulate_in(commit, offset));
+		result
+	}
+
+	fn stats_per_extension(self) -> StatsPerExtension {
+		let mut result = StatsPerExtension::default();
+		self.iter().for_each(|commit| result.accumulate(commit));
+		result
+	}
+
+	fn stats_per_directory(self, depth: usize) -> HashMap<String, SimpleStat> {
+		stats_per_file_key(&self, |file| file.path.split('/').take(depth).collect::<Vec<_>>().join("/"))
+	}
+
+	fn landing_delay_stats(&self) -> LandingDelayStats {
+		const SECONDS_PER_DAY: f64 = 86400.0;
+
+		let mut negative_count = 0;
+		let mut delays = Vec::with_capacity(self.len());
+		for commit in self.iter() {
+			let delay = commit.committer_timestamp - commit.author_timestamp;
+			if delay < 0 {
+				negative_count += 1;
 			} else {
-				*final_map.get_mut(&hour).unwrap().get_mut(&author).unwrap() += commit.into();
+				delays.push(delay as f64 / SECONDS_PER_DAY);
 			}
 		}
-		CommitsPerDayHour(final_map)
+
+		if delays.is_empty() {
+			return LandingDelayStats {
+				negative_count,
+				..Default::default()
+			};
+		}
+
+		delays.sort_by(|a, b| a.total_cmp(b));
+		let middle = delays.len() / 2;
+		let median_days = if delays.len() % 2 == 0 {
+			(delays[middle - 1] + delays[middle]) / 2.0
+		} else {
+			delays[middle]
+		};
+
+		LandingDelayStats {
+			commits_count: delays.len(),
+			average_days: delays.iter().sum::<f64>() / delays.len() as f64,
+			median_days,
+			max_days: delays[delays.len() - 1],
+			negative_count,
+		}
 	}
 
-	fn commits_heatmap(self) -> CommitsHeatMap {
-		// hashmap per author -> vec[hour] of vec[stats]
-		let mut final_map: HashMap<Author, Vec<Vec<SimpleStat>>> = HashMap::new();
-		for commit in self.into_iter() {
-			let author = commit.author.to_owned();
-
-			if !final_map.contains_key(&author) {
-				let mut rows = Vec::new();
-				for _weekday in 0..7 {
-					let mut row = Vec::new();
-					for _hour in 0..24 {
-						row.push(SimpleStat::new());
-					}
-					rows.push(row);
-				}
-				final_map.insert(author.clone(), rows);
+	fn test_coverage_commits(&self, test_globs: &[&str]) -> (usize, usize, usize) {
+		let patterns = test_globs.iter().filter_map(|glob| Pattern::new(glob).ok()).collect::<Vec<_>>();
+		let (mut tests_only, mut source_only, mut mixed) = (0, 0, 0);
+		for commit in self.iter().filter(|commit| !commit.files.is_empty()) {
+			let (tests, sources): (Vec<_>, Vec<_>) = commit
+				.files
+				.iter()
+				.partition(|file| patterns.iter().any(|pattern| pattern.matches(&file.path)));
+			match (tests.is_empty(), sources.is_empty()) {
+				(false, true) => tests_only += 1,
+				(true, false) => source_only += 1,
+				_ => mixed += 1,
 			}
+		}
+		(tests_only, source_only, mixed)
+	}
+
+	fn total(&self) -> SimpleStat {
+		self.iter().map(|commit| SimpleStat::from(commit.stats)).fold(SimpleStat::new(), |acc, item| acc + item)
+	}
 
-			let datetime = commit.get_author_datetime();
-			let weekday = datetime.weekday().num_days_from_monday() as usize;
-			let hour = datetime.hour() as usize;
+	fn churn_per_author(&self, window: Duration) -> HashMap<Author, Churn> {
+		let window = i64::try_from(window.as_secs()).unwrap_or(i64::MAX);
+		let mut commits = self.iter().collect::<Vec<_>>();
+		commits.sort_by_key(|commit| commit.author_timestamp);
+
+		// walk from the newest commit, tracking when each file is changed next
+		let mut next_change: HashMap<&str, i64> = HashMap::new();
+		let mut final_map: HashMap<Author, Churn> = HashMap::new();
+		for commit in commits.iter().rev() {
+			let churn = final_map.entry(commit.author.to_owned()).or_default();
+			for file in commit.files.iter() {
+				let churned = next_change
+					.get(file.path.as_str())
+					.is_some_and(|next| next.saturating_sub(commit.author_timestamp) <= window);
+				if churned {
+					churn.churned_lines = churn.churned_lines.saturating_add(file.lines_added);
+				} else {
+					churn.productive_lines = churn.productive_lines.saturating_add(file.lines_added);
+				}
+			}
+			for file in commit.files.iter() {
+				next_change.insert(file.path.as_str(), commit.author_timestamp);
+			}
+		}
+		final_map
+	}
 
-			*final_map
-				.get_mut(&author)
-				.unwrap()
-				.get_mut(weekday)
-				.unwrap()
-				.get_mut(hour)
-				.unwrap() += commit.into();
+	fn size_distribution(&self, thresholds: &[u64]) -> SizeHistogram {
+		let mut thresholds = thresholds.iter().copied().filter(|threshold| *threshold > 0).collect::<Vec<_>>();
+		thresholds.sort_unstable();
+		thresholds.dedup();
+
+		let mut histogram = SizeHistogram {
+			buckets: std::iter::once(0)
+				.chain(thresholds.iter().copied())
+				.zip(thresholds.iter().map(|threshold| Some(threshold - 1)).chain(std::iter::once(None)))
+				.map(|(min, max)| SizeBucket {
+					min,
+					max,
+					commits_count: 0,
+				})
+				.collect(),
+			per_author: HashMap::new(),
+		};
+		for commit in self.iter() {
+			// the number of thresholds not above the size is the index of the bucket
+			let index = thresholds.partition_point(|threshold| *threshold <= commit.stats.total_lines());
+			histogram.buckets[index].commits_count += 1;
+			let counts = histogram
+				.per_author
+				.entry(commit.author.to_owned())
+				.or_insert_with(|| vec![0; thresholds.len() + 1]);
+			counts[index] += 1;
 		}
+		histogram
+	}
+}
+
+/// Group the changed files stats by the key returned by `key_fn`.
+/// Each commit is counted once per key, regardless of how many of its files share that key.
+fn stats_per_file_key<F>(commits: &[CommitDetail], key_fn: F) -> HashMap<String, SimpleStat>
+where
+	F: Fn(&FileStat) -> String,
+{
+	let mut final_map: HashMap<String, SimpleStat> = HashMap::new();
+	for commit in commits.iter() {
+		accumulate_file_keys(&mut final_map, commit, &key_fn);
+	}
+	final_map
+}
 
-		CommitsHeatMap(final_map)
+/// Add the files of a single commit to the stats grouped by the key returned by `key_fn`
+fn accumulate_file_keys<F>(final_map: &mut HashMap<String, SimpleStat>, commit: &CommitDetail, key_fn: F)
+where
+	F: Fn(&FileStat) -> String,
+{
+	let mut commit_map: HashMap<String, CommitStats> = HashMap::new();
+	for file in commit.files.iter() {
+		*commit_map.entry(key_fn(file)).or_default() += file.into();
+	}
+
+	for (key, stats) in commit_map.into_iter() {
+		*final_map.entry(key).or_default() += stats.into();
 	}
 }
 
@@ -617,11 +1430,48 @@ impl<'a> CommitStatsExt for Vec<CommitDetail> {
 
 // region CommitsPerWeekday
 
+impl Default for CommitsPerWeekday {
+	/// Empty stats, with all the weekdays
+	fn default() -> Self {
+		CommitsPerWeekday((0..7).map(|weekday| (weekday, HashMap::new())).collect())
+	}
+}
+
 impl CommitsPerWeekday {
+	/// Add a single commit to the stats
+	pub fn accumulate(&mut self, commit: &CommitDetail) {
+		self.accumulate_in(commit, Utc.fix());
+	}
+
+	/// Same as [CommitsPerWeekday::accumulate], with the weekday in the timezone with the given `offset` from UTC
+	pub fn accumulate_in(&mut self, commit: &CommitDetail, offset: FixedOffset) {
+		let weekday = commit.get_author_datetime_in(offset).weekday().num_days_from_monday() as u8;
+		*self.0.entry(weekday).or_default().entry(commit.author.to_owned()).or_default() += commit.stats.into();
+	}
+
 	pub fn detailed_stats(&self) -> &HashMap<u8, HashMap<Author, SimpleStat>> {
 		&self.0
 	}
 
+	/// Same as [CommitsPerWeekday::detailed_stats], sorted by weekday and then by author
+	/// (name and email, case-insensitive), so that the order is the same on every run
+	pub fn sorted_detailed_stats(&self) -> Vec<(&u8, Vec<(&Author, &SimpleStat)>)> {
+		sorted_entries(&self.0).into_iter().map(|(key, stats)| (key, sorted_entries(stats))).collect()
+	}
+
+	/// Returns the total stats of the working days and of the weekend days: `(weekday_total, weekend_total)`
+	pub fn weekend_vs_weekday(&self, weekend: &Weekend) -> (SimpleStat, SimpleStat) {
+		let mut weekday_total = SimpleStat::new();
+		let mut weekend_total = SimpleStat::new();
+		for (weekday, stats) in self.global_stats() {
+			match Weekday::try_from(weekday) {
+				Ok(weekday) if weekend.contains(weekday) => weekend_total += stats,
+				_ => weekday_total += stats,
+			}
+		}
+		(weekday_total, weekend_total)
+	}
+
 	pub fn global_stats(&self) -> HashMap<u8, SimpleStat> {
 		let mut global_map: HashMap<u8, SimpleStat> = HashMap::new();
 		for (key, value) in self.0.iter() {
@@ -634,25 +1484,113 @@ impl CommitsPerWeekday {
 	}
 }
 
+impl Merge for CommitsPerWeekday {
+	fn merge(mut self, other: Self) -> Self {
+		merge_nested_maps(&mut self.0, other.0);
+		self
+	}
+}
+
 // endregion CommitsPerWeekday
 
 // region CommitsPerDayHour
 
+impl Default for CommitsPerDayHour {
+	/// Empty stats, with all the hours of the day
+	fn default() -> Self {
+		CommitsPerDayHour((0..24).map(|hour| (hour, HashMap::new())).collect())
+	}
+}
+
 impl CommitsPerDayHour {
+	/// Add a single commit to the stats
+	pub fn accumulate(&mut self, commit: &CommitDetail) {
+		self.accumulate_in(commit, Utc.fix());
+	}
+
+	/// Same as [CommitsPerDayHour::accumulate], with the hour in the timezone with the given `offset` from UTC
+	pub fn accumulate_in(&mut self, commit: &CommitDetail, offset: FixedOffset) {
+		let hour = commit.get_author_datetime_in(offset).hour();
+		*self.0.entry(hour).or_default().entry(commit.author.to_owned()).or_default() += commit.stats.into();
+	}
+
 	pub fn detailed_stats(&self) -> &HashMap<u32, HashMap<Author, SimpleStat>> {
 		&self.0
 	}
 
+	/// Same as [CommitsPerDayHour::detailed_stats], sorted by hour and then by author
+	/// (name and email, case-insensitive), so that the order is the same on every run
+	pub fn sorted_detailed_stats(&self) -> Vec<(&u32, Vec<(&Author, &SimpleStat)>)> {
+		sorted_entries(&self.0).into_iter().map(|(key, stats)| (key, sorted_entries(stats))).collect()
+	}
+
 	pub fn global_stats(&self) -> HashMap<u32, SimpleStat> {
 		let mut global_map: HashMap<u32, SimpleStat> = HashMap::new();
 		for (key, value) in self.0.iter() {
-			global_map.insert(key.clone(), SimpleStat::new());
+			global_map.insert(*key, SimpleStat::new());
 			for (_, stats) in value.iter() {
 				*global_map.get_mut(key).unwrap() += stats.clone();
 			}
 		}
 		global_map
 	}
+
+	/// Returns the hour with the most commits, with its stats. Ties are broken by the lowest hour
+	pub fn peak_hour(&self) -> (u32, SimpleStat) {
+		self.find_hour(|count, best| count > best)
+	}
+
+	/// Returns the hour with the fewest commits (possibly none), with its stats. Ties are broken by the lowest hour
+	pub fn quietest_hour(&self) -> (u32, SimpleStat) {
+		self.find_hour(|count, best| count < best)
+	}
+
+	/// Returns the first hour of the day whose commits count is `better` than all the previous hours
+	fn find_hour(&self, better: impl Fn(usize, usize) -> bool) -> (u32, SimpleStat) {
+		let mut global_stats = self.global_stats();
+		let mut hours = (0..24).map(|hour| (hour, global_stats.remove(&hour).unwrap_or_default()));
+		let first = hours.next().unwrap();
+		hours.fold(first, |best, hour| if better(hour.1.commits_count, best.1.commits_count) { hour } else { best })
+	}
+
+	/// Returns the author with the most commits in each part of the day: `morning` (6-12), `afternoon` (12-18),
+	/// `evening` (18-22) and `night` (22-6). Ties are broken by name.
+	/// Parts of the day without commits are not included.
+	pub fn part_of_day_leaders(&self) -> HashMap<&'static str, Author> {
+		let mut commits: HashMap<&'static str, HashMap<&Author, usize>> = HashMap::new();
+		for (hour, authors) in self.0.iter() {
+			let part_of_day = match hour {
+				6..=11 => "morning",
+				12..=17 => "afternoon",
+				18..=21 => "evening",
+				_ => "night",
+			};
+			for (author, stat) in authors.iter().filter(|(_, stat)| stat.commits_count > 0) {
+				*commits.entry(part_of_day).or_default().entry(author).or_default() += stat.commits_count;
+			}
+		}
+
+		commits
+			.into_iter()
+			.filter_map(|(part_of_day, authors)| {
+				authors
+					.into_iter()
+					.max_by(|(a1, c1), (a2, c2)| {
+						c1.cmp(c2)
+							.then_with(|| cmp_ignore_case(&a2.name, &a1.name))
+							.then_with(|| a2.identity_cmp(a1))
+					})
+					.map(|(author, _)| (part_of_day, author.clone()))
+			})
+			.collect()
+	}
+}
+
+impl Merge for CommitsPerDayHour {
+	fn merge(mut self, other: Self) -> Self {
+		merge_nested_maps(&mut self.0, other.0);
+		self
+	}
 }
 
 // endregion CommitsPerDayHour
@@ -660,10 +1598,28 @@ impl CommitsPerDayHour {
 // region CommitsPerMonth
 
 impl CommitsPerMonth {
+	/// Add a single commit to the stats.
+	/// Unlike [CommitStatsExt::commits_per_month], the months without commits are not added.
+	pub fn accumulate(&mut self, commit: &CommitDetail) {
+		self.accumulate_in(commit, Utc.fix());
+	}
+
+	/// Same as [CommitsPerMonth::accumulate], with the month in the timezone with the given `offset` from UTC
+	pub fn accumulate_in(&mut self, commit: &CommitDetail, offset: FixedOffset) {
+		let month = commit.get_author_datetime_in(offset).format("%Y-%m").to_string();
+		*self.0.entry(month).or_default().entry(commit.author.to_owned()).or_default() += commit.stats.into();
+	}
+
 	pub fn detailed_stats(&self) -> &HashMap<String, HashMap<Author, SimpleStat>> {
 		&self.0
 	}
 
+	/// Same as [CommitsPerMonth::detailed_stats], sorted by month and then by author
+	/// (name and email, case-insensitive), so that the order is the same on every run
+	pub fn sorted_detailed_stats(&self) -> Vec<(&String, Vec<(&Author, &SimpleStat)>)> {
+		sorted_entries(&self.0).into_iter().map(|(key, stats)| (key, sorted_entries(stats))).collect()
+	}
+
 	pub fn global_stats(&self) -> HashMap<String, SimpleStat> {
 		let mut global_map: HashMap<String, SimpleStat> = HashMap::new();
 		for (key, value) in self.0.iter() {
@@ -674,17 +1630,228 @@ impl CommitsPerMonth {
 		}
 		global_map
 	}
+
+	/// Returns the number of commits of each month smoothed with a trailing moving average over `window` months,
+	/// ordered by month. Every month between the first and the last one is part of the series, the months
+	/// without commits count as zero. The first months of the series are averaged over the available months.
+	/// A `window` of 0 or 1 returns the raw number of commits.
+	pub fn moving_average(&self, window: usize) -> Vec<(String, f64)> {
+		let window = window.max(1);
+		let counts = self
+			.global_stats()
+			.into_iter()
+			.filter_map(|(key, stat)| month_index(&key).map(|index| (index, stat.commits_count)))
+			.collect::<HashMap<_, _>>();
+		let (Some(first), Some(last)) = (counts.keys().min(), counts.keys().max()) else {
+			return Vec::new();
+		};
+
+		let series = (*first..=*last).map(|index| counts.get(&index).copied().unwrap_or(0)).collect::<Vec<_>>();
+		(0..series.len())
+			.map(|position| {
+				let values = &series[(position + 1).saturating_sub(window)..=position];
+				let average = values.iter().sum::<usize>() as f64 / values.len() as f64;
+				(month_key(first + position as i32), average)
+			})
+			.collect()
+	}
+
+	/// Returns the contributors retention, grouping the authors by the month of their first commit (the cohort).
+	/// For each cohort month the vector contains, at index `n`, the number of authors of that cohort who
+	/// committed `n` months after the cohort month, up to the last month available.
+	pub fn cohort_retention(&self) -> HashMap<String, Vec<usize>> {
+		let mut months = self
+			.0
+			.iter()
+			.filter_map(|(key, value)| month_index(key).map(|index| (index, key, value)))
+			.collect::<Vec<_>>();
+		months.sort_by_key(|(index, _, _)| *index);
+
+		let last_index = match months.last() {
+			Some((index, _, _)) => *index,
+			None => return HashMap::new(),
+		};
+
+		// author -> (cohort key, cohort month index)
+		let mut cohorts: HashMap<&Author, (&String, i32)> = HashMap::new();
+		let mut result: HashMap<String, Vec<usize>> = HashMap::new();
+
+		for (index, key, authors) in months.iter() {
+			for (author, stat) in authors.iter() {
+				if stat.commits_count == 0 {
+					continue;
+				}
+
+				let (cohort_key, cohort_index) = *cohorts.entry(author).or_insert((key, *index));
+				let retention = result
+					.entry(cohort_key.to_string())
+					.or_insert_with(|| vec![0; (last_index - cohort_index + 1) as usize]);
+				retention[(index - cohort_index) as usize] += 1;
+			}
+		}
+		result
+	}
+}
+
+/// As for [CommitStatsExt::commits_per_month], every month between the first and the last month of the merged
+/// stats is a key, even without commits: merging January-February with May-June adds March and April
+impl Merge for CommitsPerMonth {
+	fn merge(mut self, other: Self) -> Self {
+		merge_nested_maps(&mut self.0, other.0);
+		let months = self.0.keys().filter_map(|key| month_index(key)).collect::<Vec<_>>();
+		if let (Some(first), Some(last)) = (months.iter().min(), months.iter().max()) {
+			for index in *first..=*last {
+				self.0.entry(month_key(index)).or_default();
+			}
+		}
+		self
+	}
+}
+
+/// Parse a `%Y-%m` key into the number of months since year 0
+fn month_index(key: &str) -> Option<i32> {
+	let (year, month) = key.split_once('-')?;
+	let year = year.parse::<i32>().ok()?;
+	let month = month.parse::<i32>().ok()?;
+	Some(year * 12 + month - 1)
+}
+
+/// Inverse of [month_index]
+fn month_key(index: i32) -> String {
+	format!("{:04}-{:02}", index.div_euclid(12), index.rem_euclid(12) + 1)
 }
 
 // endregion CommitsPerMonth
 
+// region CommitsPerWeek
+
+impl CommitsPerWeek {
+	/// Add a single commit to the stats.
+	/// Unlike [CommitStatsExt::commits_per_week], the weeks without commits are not added.
+	pub fn accumulate(&mut self, commit: &CommitDetail) {
+		self.accumulate_in(commit, Utc.fix());
+	}
+
+	/// Same as [CommitsPerWeek::accumulate], with the week in the timezone with the given `offset` from UTC
+	pub fn accumulate_in(&mut self, commit: &CommitDetail, offset: FixedOffset) {
+		let week = week_key(commit.get_author_datetime_in(offset).date_naive());
+		*self.0.entry(week).or_default().entry(commit.author.to_owned()).or_default() += commit.stats.into();
+	}
+
+	pub fn detailed_stats(&self) -> &HashMap<String, HashMap<Author, SimpleStat>> {
+		&self.0
+	}
+
+	/// Same as [CommitsPerWeek::detailed_stats], sorted by week and then by author
+	/// (name and email, case-insensitive), so that the order is the same on every run
+	pub fn sorted_detailed_stats(&self) -> Vec<(&String, Vec<(&Author, &SimpleStat)>)> {
+		sorted_entries(&self.0).into_iter().map(|(key, stats)| (key, sorted_entries(stats))).collect()
+	}
+
+	pub fn global_stats(&self) -> HashMap<String, SimpleStat> {
+		let mut global_map: HashMap<String, SimpleStat> = HashMap::new();
+		for (key, value) in self.0.iter() {
+			let global = global_map.entry(key.clone()).or_default();
+			for stats in value.values() {
+				*global += stats.clone();
+			}
+		}
+		global_map
+	}
+}
+
+/// As for [CommitStatsExt::commits_per_week], every week between the first and the last week of the merged
+/// stats is a key, even without commits
+impl Merge for CommitsPerWeek {
+	fn merge(mut self, other: Self) -> Self {
+		merge_nested_maps(&mut self.0, other.0);
+		let weeks = self.0.keys().filter_map(|key| week_monday(key)).collect::<Vec<_>>();
+		if let (Some(first), Some(last)) = (weeks.iter().min(), weeks.iter().max()) {
+			let mut current_week = *first;
+			while current_week <= *last {
+				self.0.entry(week_key(current_week)).or_default();
+				current_week = current_week + Days::new(7);
+			}
+		}
+		self
+	}
+}
+
+/// The `%G-W%V` key of the ISO week of the given day, e.g. `2024-W07`
+fn week_key(date: NaiveDate) -> String {
+	let week = date.iso_week();
+	format!("{:04}-W{:02}", week.year(), week.week())
+}
+
+/// Inverse of [week_key], returning the Monday of the week
+fn week_monday(key: &str) -> Option<NaiveDate> {
+	let (year, week) = key.split_once("-W")?;
+	NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+}
+
+// endregion CommitsPerWeek
+
+// region StatsPerExtension
+
+impl StatsPerExtension {
+	/// Add the files of a single commit to the stats
+	pub fn accumulate(&mut self, commit: &CommitDetail) {
+		accumulate_file_keys(&mut self.0, commit, |file| file.extension().to_string());
+	}
+
+	pub fn stats(&self) -> &HashMap<String, SimpleStat> {
+		&self.0
+	}
+
+	/// Same as [StatsPerExtension::stats], sorted by extension
+	pub fn sorted_stats(&self) -> Vec<(&String, &SimpleStat)> {
+		sorted_entries(&self.0)
+	}
+}
+
+impl Merge for StatsPerExtension {
+	fn merge(mut self, other: Self) -> Self {
+		for (extension, stat) in other.0 {
+			*self.0.entry(extension).or_default() += stat;
+		}
+		self
+	}
+}
+
+// endregion StatsPerExtension
+
 // region CommitsHeatmap
 
 impl CommitsHeatMap {
+	/// Add a single commit to the stats. The weekday x hour grid of an author is created on their first commit
+	pub fn accumulate(&mut self, commit: &CommitDetail) {
+		self.accumulate_in(commit, Utc.fix());
+	}
+
+	/// Same as [CommitsHeatMap::accumulate], with the weekday and hour in the timezone with the given `offset`
+	/// from UTC
+	pub fn accumulate_in(&mut self, commit: &CommitDetail, offset: FixedOffset) {
+		let datetime = commit.get_author_datetime_in(offset);
+		let weekday = datetime.weekday().num_days_from_monday() as usize;
+		let hour = datetime.hour() as usize;
+
+		let rows = self
+			.0
+			.entry(commit.author.to_owned())
+			.or_insert_with(|| vec![vec![SimpleStat::new(); 24]; 7]);
+		rows[weekday][hour] += commit.stats.into();
+	}
+
 	pub fn detailed_stats(&self) -> &HashMap<Author, Vec<Vec<SimpleStat>>> {
 		&self.0
 	}
 
+	/// Same as [CommitsHeatMap::detailed_stats], sorted by author (name and email, case-insensitive),
+	/// so that the order is the same on every run
+	pub fn sorted_detailed_stats(&self) -> Vec<(&Author, &Vec<Vec<SimpleStat>>)> {
+		sorted_entries(&self.0)
+	}
+
 	pub fn global_stats(&self) -> Vec<Vec<SimpleStat>> {
 		// weekday x hour
 
@@ -707,10 +1874,134 @@ impl CommitsHeatMap {
 
 		final_map
 	}
+
+	/// Same as [CommitsHeatMap::global_stats], with the weekday axis starting from `week_start`: with
+	/// [WeekStart::Sunday] the row 0 is Sunday and the row 6 is Saturday.
+	/// [WeekStart::weekdays] returns the weekday of each row.
+	pub fn global_stats_week_start(&self, week_start: WeekStart) -> Vec<Vec<SimpleStat>> {
+		let mut stats = self.global_stats();
+		if week_start == WeekStart::Sunday {
+			stats.rotate_right(1);
+		}
+		stats
+	}
+
+	/// The commits count of each cell of [CommitsHeatMap::global_stats], relative to the busiest cell: the
+	/// values go from 0.0 to 1.0, where 1.0 is the cell with the most commits. All zeros if there are no commits.
+	pub fn normalized_global(&self) -> Vec<Vec<f64>> {
+		let stats = self.global_stats();
+		let max = stats.iter().flatten().map(|stat| stat.commits_count).max().unwrap_or(0);
+		stats
+			.iter()
+			.map(|row| {
+				row.iter()
+					.map(|stat| if max == 0 { 0.0 } else { stat.commits_count as f64 / max as f64 })
+					.collect()
+			})
+			.collect()
+	}
+
+	/// Returns the weekday and the hour with the most commits of all the authors, with their stats.
+	/// Ties are broken by the earliest slot of the week, from Monday at 0, which is also returned without commits
+	pub fn busiest_slot(&self) -> (Weekday, u32, SimpleStat) {
+		busiest_cell(&self.global_stats())
+	}
+
+	/// Same as [CommitsHeatMap::busiest_slot] for a single author, `None` if the author has no commits
+	pub fn peak_slot(&self, author: &Author) -> Option<(Weekday, u32, SimpleStat)> {
+		self.0.get(author).map(|rows| busiest_cell(rows))
+	}
+
+	/// Render the global stats as a GitHub flavored markdown table, with a row per weekday and a column per hour.
+	/// Each cell contains the number of commits.
+	pub fn to_markdown(&self) -> String {
+		let mut lines = Vec::with_capacity(9);
+		let hours = (0..24).map(|hour| hour.to_string()).collect::<Vec<_>>();
+		lines.push(format!("| Weekday/Hour | {} |", hours.join(" | ")));
+		lines.push(format!("| --- |{}", " ---: |".repeat(24)));
+
+		for (weekday, row) in self.global_stats().iter().enumerate() {
+			let weekday = Weekday::try_from(weekday as u8).unwrap();
+			let cells = row.iter().map(|stat| stat.commits_count.to_string()).collect::<Vec<_>>();
+			lines.push(format!("| {} | {} |", weekday, cells.join(" | ")));
+		}
+		lines.join("\n")
+	}
+}
+
+/// The grids of the same author are added cell by cell
+impl Merge for CommitsHeatMap {
+	fn merge(mut self, other: Self) -> Self {
+		merge_author_maps(&mut self.0, other.0, |rows, other_rows| {
+			for (row, other_row) in rows.iter_mut().zip(other_rows) {
+				for (cell, other_cell) in row.iter_mut().zip(other_row) {
+					*cell += other_cell;
+				}
+			}
+		});
+		self
+	}
+}
+
+/// Returns the first cell of the weekday x hour grid with the most commits
+fn busiest_cell(rows: &[Vec<SimpleStat>]) -> (Weekday, u32, SimpleStat) {
+	let mut busiest = (Weekday::Mon, 0, SimpleStat::default());
+	for (weekday, row) in rows.iter().enumerate() {
+		for (hour, stat) in row.iter().enumerate() {
+			if stat.commits_count > busiest.2.commits_count {
+				busiest = (Weekday::try_from(weekday as u8).unwrap(), hour as u32, stat.clone());
+			}
+		}
+	}
+	busiest
 }
 
 // endregion CommitsHeatmap
 
+// region Weekend
+
+impl Weekend {
+	pub fn new(days: &[Weekday]) -> Self {
+		Weekend(days.to_vec())
+	}
+
+	/// Friday and Saturday, as in most of the Middle East
+	pub fn friday_saturday() -> Self {
+		Weekend::new(&[Weekday::Fri, Weekday::Sat])
+	}
+
+	pub fn contains(&self, weekday: Weekday) -> bool {
+		self.0.contains(&weekday)
+	}
+}
+
+impl Default for Weekend {
+	fn default() -> Self {
+		Weekend::new(&[Weekday::Sat, Weekday::Sun])
+	}
+}
+
+// endregion Weekend
+
+// region WeekStart
+
+impl WeekStart {
+	/// Returns the days of the week, starting from this day
+	pub fn weekdays(&self) -> [Weekday; 7] {
+		let mut weekday = match self {
+			WeekStart::Monday => Weekday::Mon,
+			WeekStart::Sunday => Weekday::Sun,
+		};
+		std::array::from_fn(|_| {
+			let current = weekday;
+			weekday = weekday.succ();
+			current
+		})
+	}
+}
+
+// endregion WeekStart
+
 // region CommitsPerAuthor
 
 impl CommitsPerAuthor {
@@ -718,10 +2009,72 @@ impl CommitsPerAuthor {
 		&self.0
 	}
 
+	/// Same as [CommitsPerAuthor::detailed_stats], sorted by author (name and email, case-insensitive),
+	/// so that the order is the same on every run
+	pub fn sorted_detailed_stats(&self) -> Vec<(&Author, &Vec<MinimalCommitDetail>)> {
+		sorted_entries(&self.0)
+	}
+
+	/// Returns the per-author global stats, sorted using the default order of `sort_stats_by`
+	/// (descending for the counters, alphabetical for name and email)
 	pub fn global_stats(&self, sort_stats_by: SortStatsBy) -> Vec<GlobalStat> {
-		let mut global_stats = self
-			.0
+		let sort_order = sort_stats_by.default_order();
+		self.global_stats_ordered(sort_stats_by, sort_order)
+	}
+
+	/// Returns the per-author global stats, sorted by `sort_stats_by` in the given `sort_order`.
+	/// Authors with the same value are sorted by identity, so that the order is always the same.
+	pub fn global_stats_ordered(&self, sort_stats_by: SortStatsBy, sort_order: SortOrder) -> Vec<GlobalStat> {
+		let mut global_stats = self.unsorted_global_stats();
+		global_stats.sort_by(|a, b| cmp_global_stats(a, b, sort_stats_by, sort_order));
+		global_stats
+	}
+
+	/// Same as [CommitsPerAuthor::global_stats], with the share of each author in the totals of all the authors.
+	/// The shares are 0 when the total is 0 (e.g. no commits, or no deleted lines). When the co-authors are
+	/// credited with [CoAuthorMode::Full], the commits are counted more than once and so are the totals.
+	pub fn global_stats_with_shares(&self, sort_stats_by: SortStatsBy) -> Vec<(GlobalStat, Shares)> {
+		let global_stats = self.global_stats(sort_stats_by);
+		let mut total = SimpleStat::new();
+		for stat in global_stats.iter() {
+			total.commits_count += stat.commits_count;
+			total.stats += stat.stats;
+		}
+
+		global_stats
+			.into_iter()
+			.map(|stat| {
+				let shares = Shares {
+					commits_pct: percentage(stat.commits_count as f64, total.commits_count as f64),
+					lines_added_pct: percentage(stat.stats.lines_added as f64, total.stats.lines_added as f64),
+					lines_deleted_pct: percentage(stat.stats.lines_deleted as f64, total.stats.lines_deleted as f64),
+				};
+				(stat, shares)
+			})
+			.collect()
+	}
+
+	/// Returns at most `n` entries of the global stats, sorted using the default order of `sort_stats_by`.
+	/// Same as taking the first `n` items of [CommitsPerAuthor::global_stats], without sorting all the authors.
+	pub fn top_n(&self, n: usize, sort_stats_by: SortStatsBy) -> Vec<GlobalStat> {
+		let sort_order = sort_stats_by.default_order();
+		let mut global_stats = self.unsorted_global_stats();
+		let compare = |a: &GlobalStat, b: &GlobalStat| cmp_global_stats(a, b, sort_stats_by, sort_order);
+		if n == 0 {
+			return Vec::new();
+		} else if n < global_stats.len() {
+			global_stats.select_nth_unstable_by(n - 1, compare);
+			global_stats.truncate(n);
+		}
+		global_stats.sort_by(compare);
+		global_stats
+	}
+
+	fn unsorted_global_stats(&self) -> Vec<GlobalStat> {
+		// a deserialized or hand-built value may have authors without commits
+		self.0
 			.iter()
+			.filter(|(_, value)| !value.is_empty())
 			.map(|(key, value)| {
 				let stats = value.iter().map(|item| item.stats).reduce(|acc, item| acc + item).unwrap();
 				let total_commits = value.len();
@@ -729,24 +2082,212 @@ impl CommitsPerAuthor {
 					author: Author::from(key),
 					commits_count: total_commits,
 					stats,
+					distinct_files: value.iter().flat_map(|item| item.files.iter()).collect::<HashSet<_>>().len(),
+					first_commit: value.iter().map(|item| item.author_timestamp).min().unwrap(),
+					last_commit: value.iter().map(|item| item.author_timestamp).max().unwrap(),
+				}
+			})
+			.collect::<Vec<_>>()
+	}
+
+	/// Merge the authors sharing the same email (case-insensitive), regardless of their name, e.g. after a name
+	/// change. The canonical author of each group is the one with the most recent commit.
+	/// Authors without an email are never merged.
+	pub fn merge_by_email(&self) -> CommitsPerAuthor {
+		let mut groups: HashMap<String, Vec<(&Author, &Vec<MinimalCommitDetail>)>> = HashMap::new();
+		let mut result: HashMap<Author, Vec<MinimalCommitDetail>> = HashMap::new();
+		for (author, commits) in self.0.iter() {
+			match author.email.as_ref() {
+				Some(email) => groups.entry(email.to_lowercase()).or_default().push((author, commits)),
+				None => {
+					result.insert(author.clone(), commits.clone());
 				}
+			}
+		}
+
+		for (_, group) in groups {
+			let latest = |commits: &Vec<MinimalCommitDetail>| commits.iter().map(|c| c.author_timestamp).max();
+			let (canonical, _) = group
+				.iter()
+				.max_by(|(a1, c1), (a2, c2)| latest(c1).cmp(&latest(c2)).then_with(|| a2.identity_cmp(a1)))
+				.unwrap();
+			let mut commits = group.iter().flat_map(|(_, commits)| commits.iter().cloned()).collect::<Vec<_>>();
+			commits.sort_by_key(|commit| commit.author_timestamp);
+			result.insert((*canonical).clone(), commits);
+		}
+		CommitsPerAuthor(result)
+	}
+
+	/// Replace every author with a pseudonym (`Author A`, `Author B`, ..., `Author Z`, `Author AA`, ...) assigned by
+	/// contribution: the author with most commits (then most changed lines) is `Author A`.
+	/// The assignment is deterministic, the same stats always produce the same pseudonyms.
+	/// Returns the anonymized stats and the mapping from each pseudonym to the original author.
+	pub fn anonymize(&self) -> (CommitsPerAuthor, HashMap<Author, Author>) {
+		let mut authors = self
+			.0
+			.iter()
+			.map(|(author, commits)| {
+				let stats = commits.iter().fold(CommitStats::default(), |acc, item| acc + item.stats);
+				(author, commits.len(), stats.total_lines())
 			})
 			.collect::<Vec<_>>();
+		authors.sort_by(|(a1, commits1, lines1), (a2, commits2, lines2)| {
+			commits2
+				.cmp(commits1)
+				.then_with(|| lines2.cmp(lines1))
+				.then_with(|| cmp_ignore_case(&a1.name, &a2.name))
+				.then_with(|| a1.identity_cmp(a2))
+		});
+
+		let mut anonymized = HashMap::with_capacity(authors.len());
+		let mut mapping = HashMap::with_capacity(authors.len());
+		for (index, (author, _, _)) in authors.into_iter().enumerate() {
+			let pseudonym = Author::new(format!("Author {}", pseudonym_letters(index)));
+			anonymized.insert(pseudonym.clone(), self.0[author].clone());
+			mapping.insert(pseudonym, author.clone());
+		}
+		(CommitsPerAuthor(anonymized), mapping)
+	}
+
+	/// Returns the bus factor: the minimum number of authors that account for at least `threshold`
+	/// (e.g. `0.5` for 50%) of the changes.
+	/// The changes of an author are the lines added plus the lines deleted by all their commits, and the authors
+	/// are counted from the biggest contributor down. `threshold` is clamped to `0.0..=1.0`.
+	/// Returns 0 when there are no changed lines at all (or the threshold is 0).
+	pub fn bus_factor(&self, threshold: f64) -> usize {
+		let mut changes = self
+			.0
+			.values()
+			.map(|commits| commits.iter().map(|commit| commit.stats.total_lines()).sum::<u64>())
+			.collect::<Vec<_>>();
+		let total = changes.iter().sum::<u64>();
+		let threshold = threshold.clamp(0.0, 1.0);
+		if total == 0 || threshold == 0.0 {
+			return 0;
+		}
 
-		match sort_stats_by {
-			SortStatsBy::Commits => global_stats.sort_by_key(|item| item.commits_count),
-			SortStatsBy::FilesChanged => global_stats.sort_by_key(|item| item.stats.files_changed),
-			SortStatsBy::LinesAdded => global_stats.sort_by_key(|item| item.stats.lines_added),
-			SortStatsBy::LinesDeleted => global_stats.sort_by_key(|item| item.stats.lines_deleted),
+		changes.sort_unstable_by(|a, b| b.cmp(a));
+		let mut accumulated = 0;
+		for (index, value) in changes.iter().enumerate() {
+			accumulated += value;
+			if accumulated as f64 / total as f64 >= threshold {
+				return index + 1;
+			}
 		}
+		changes.len()
+	}
 
-		global_stats.reverse();
-		global_stats
+	/// Returns the median size of the commits of each author, in lines added plus lines deleted.
+	/// With an even number of commits, the median is the average of the two middle sizes.
+	pub fn median_lines_per_commit(&self) -> HashMap<Author, f64> {
+		self.0
+			.iter()
+			.filter(|(_, commits)| !commits.is_empty())
+			.map(|(author, commits)| {
+				let mut sizes = commits.iter().map(|commit| commit.stats.total_lines()).collect::<Vec<_>>();
+				sizes.sort_unstable();
+				let middle = sizes.len() / 2;
+				let median = if sizes.len() % 2 == 0 {
+					(sizes[middle - 1] + sizes[middle]) as f64 / 2.0
+				} else {
+					sizes[middle] as f64
+				};
+				(author.clone(), median)
+			})
+			.collect()
+	}
+
+	/// Returns the activity streaks of each author (see [StreakInfo]).
+	/// The current streak is relative to the most recent commit of the stats, not to the current date,
+	/// so the result only depends on the commits.
+	pub fn streaks(&self) -> HashMap<Author, StreakInfo> {
+		const SECONDS_PER_DAY: i64 = 86_400;
+		let last_day = self.0.values().flatten().map(|commit| commit.author_timestamp.div_euclid(SECONDS_PER_DAY)).max();
+
+		self.0
+			.iter()
+			.filter(|(_, commits)| !commits.is_empty())
+			.map(|(author, commits)| {
+				let mut days = commits
+					.iter()
+					.map(|commit| commit.author_timestamp.div_euclid(SECONDS_PER_DAY))
+					.collect::<Vec<_>>();
+				days.sort_unstable();
+				days.dedup();
+
+				let mut longest = 0;
+				let mut current = 0;
+				for (index, day) in days.iter().enumerate() {
+					current = if index > 0 && days[index - 1] + 1 == *day { current + 1 } else { 1 };
+					longest = longest.max(current);
+				}
+
+				let info = StreakInfo {
+					longest_consecutive_days: longest,
+					current_streak: if days.last() == last_day.as_ref() { current } else { 0 },
+					active_days: days.len() as u32,
+					first_commit: commits.iter().map(|commit| commit.author_timestamp).min().unwrap(),
+					last_commit: commits.iter().map(|commit| commit.author_timestamp).max().unwrap(),
+				};
+				(author.clone(), info)
+			})
+			.collect()
+	}
+}
+
+fn cmp_global_stats(a: &GlobalStat, b: &GlobalStat, sort_stats_by: SortStatsBy, sort_order: SortOrder) -> Ordering {
+	let ordering = match sort_stats_by {
+		SortStatsBy::Commits => a.commits_count.cmp(&b.commits_count),
+		SortStatsBy::FilesChanged => a.stats.files_changed.cmp(&b.stats.files_changed),
+		SortStatsBy::LinesAdded => a.stats.lines_added.cmp(&b.stats.lines_added),
+		SortStatsBy::LinesDeleted => a.stats.lines_deleted.cmp(&b.stats.lines_deleted),
+		SortStatsBy::Name => cmp_ignore_case(&a.author.name, &b.author.name),
+		SortStatsBy::Email => cmp_ignore_case(a.email(), b.email()),
+	};
+	let ordering = match sort_order {
+		SortOrder::Ascending => ordering,
+		SortOrder::Descending => ordering.reverse(),
+	};
+	ordering.then_with(|| a.author.identity_cmp(&b.author))
+}
+
+/// The commits of the same author are concatenated
+impl Merge for CommitsPerAuthor {
+	fn merge(mut self, other: Self) -> Self {
+		merge_author_maps(&mut self.0, other.0, |commits, other_commits| commits.extend(other_commits));
+		self
 	}
 }
 
+/// Returns the letters of the pseudonym at the given index: `A`...`Z`, `AA`...`AZ`, `BA`...
+fn pseudonym_letters(index: usize) -> String {
+	let mut letters = Vec::new();
+	let mut index = index + 1;
+	while index > 0 {
+		index -= 1;
+		letters.push((b'A' + (index % 26) as u8) as char);
+		index /= 26;
+	}
+	letters.iter().rev().collect()
+}
+
 // endregion CommitsPerAuthor
 
+// region SizeBucket
+
+/// The range of the bucket: `0`, `10-49` or `1000+`
+impl Display for SizeBucket {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self.max {
+			Some(max) if max == self.min => write!(f, "{}", self.min),
+			Some(max) => write!(f, "{}-{}", self.min, max),
+			None => write!(f, "{}+", self.min),
+		}
+	}
+}
+
+// endregion SizeBucket
+
 // region Detail
 
 impl Display for Detail {
@@ -755,14 +2296,12 @@ impl Display for Detail {
 		strings.push(format!("size={}", self.size));
 		strings.push(format!("commits_count={}", self.commits_count));
 		if let Some(value) = self.first_commit {
-			if let Some(naive) = NaiveDateTime::from_timestamp_opt(value, 0) {
-				let datetime: DateTime<Utc> = DateTime::from_naive_utc_and_offset(naive, Utc);
+			if let Some(datetime) = DateTime::from_timestamp(value, 0) {
 				strings.push(format!("first_commit={}", datetime));
 			}
 		}
 		if let Some(value) = self.last_commit {
-			if let Some(naive) = NaiveDateTime::from_timestamp_opt(value, 0) {
-				let datetime: DateTime<Utc> = DateTime::from_naive_utc_and_offset(naive, Utc);
+			if let Some(datetime) = DateTime::from_timestamp(value, 0) {
 				strings.push(format!("last_commit={}", datetime));
 			}
 		}
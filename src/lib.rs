@@ -1,22 +1,129 @@
+#![allow(clippy::tabs_in_doc_comments)]
+
 use std::collections::HashMap;
+#[cfg(feature = "repo")]
 use std::path::PathBuf;
+#[cfg(feature = "repo")]
+use std::time::Duration;
 
+use chrono::Weekday;
 use serde::{Deserialize, Serialize};
 
 mod impls;
+#[cfg(feature = "repo")]
 mod repo;
+pub mod ser;
 mod test;
 pub mod traits;
 
+#[cfg(feature = "repo")]
 #[derive(Debug, Clone)]
 pub struct Repo {
 	inner: PathBuf,
+	timeout: Option<Duration>,
+	merge_diff_mode: MergeDiffMode,
+	/// see [Repo::with_max_stat_files]
+	max_stat_files: Option<u32>,
+	/// the git executable, searched in the `PATH` when `None`
+	git_binary: Option<PathBuf>,
+}
+
+/// How the stats of the merge commits are computed, see [Repo::with_merge_diff_mode]
+#[cfg(feature = "repo")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeDiffMode {
+	/// Merge commits have empty stats (`--diff-merges=off`)
+	#[default]
+	Off,
+	/// The diff against the first parent, i.e. all the changes brought in by the merge (`--diff-merges=first-parent`)
+	FirstParent,
+	/// The sum of the diffs against each parent (`-m`, `--diff-merges=separate`)
+	Separate,
+}
+
+/// A remote of the repository, see [Repo::list_remotes]
+#[cfg(feature = "repo")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Remote {
+	pub name: String,
+	pub fetch_url: String,
+	/// the push url, `None` if it's the same as the fetch url
+	pub push_url: Option<String>,
+}
+
+/// The interval between two consecutive tags, see [Repo::tag_cadence]
+#[cfg(feature = "repo")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagInterval {
+	/// the older tag
+	pub from: String,
+	/// the newer tag
+	pub to: String,
+	/// days between the creation dates of the two tags
+	pub days: f64,
+	/// number of commits reachable from `to` but not from `from`
+	pub commits: usize,
+}
+
+/// The commits and the stats of an author, see [Repo::author_summary]
+#[cfg(feature = "repo")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorSummary {
+	pub author: Author,
+	pub commits_count: usize,
+	/// author timestamp of the first commit
+	pub first_commit: i64,
+	/// author timestamp of the last commit
+	pub last_commit: i64,
+	pub stats: CommitStats,
+}
+
+/// Summary of the working tree, see [Repo::status].
+/// A file with both staged and unstaged changes is counted in `staged` and in `modified`
+#[cfg(feature = "repo")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkTreeStatus {
+	/// files with changes in the index
+	pub staged: usize,
+	/// tracked files with changes not yet staged
+	pub modified: usize,
+	/// files not tracked by git (ignored files are not counted)
+	pub untracked: usize,
+	/// files with unresolved merge conflicts
+	pub conflicted: usize,
+}
+
+/// Errors returned by the crate.
+/// The git operations wrap them in an `anyhow::Error`, they can be retrieved with `downcast_ref`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("failed to parse author `{0}`")]
+	InvalidAuthor(String),
+	#[error("invalid commit hash `{0}`")]
+	InvalidCommitHash(String),
+	/// Conflicting or out of range [CommitArgs]
+	#[error("{0}")]
+	InvalidArgs(String),
+	#[cfg(feature = "repo")]
+	#[error("git command timed out after {0:?}")]
+	Timeout(Duration),
+	#[cfg(feature = "repo")]
+	#[error("not a git repository: {0:?}")]
+	NotARepository(PathBuf),
+	#[cfg(feature = "repo")]
+	#[error("unknown commit `{0}`")]
+	UnknownCommit(String),
+	/// The output of git is not in the expected format
+	#[cfg(feature = "repo")]
+	#[error("failed to parse the git output: {0}")]
+	Parse(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitHash(String);
 
 #[derive(Debug, Default, Hash, Clone, Serialize, Deserialize)]
+#[allow(clippy::derived_hash_with_manual_eq)]
 pub struct Author {
 	pub name: String,
 	pub email: Option<String>,
@@ -26,76 +133,301 @@ pub struct Author {
 pub struct CommitArgs {
 	since: Option<i64>,
 	until: Option<i64>,
-	author: Option<Author>,
+	since_relative: Option<String>,
+	until_relative: Option<String>,
+	authors: Vec<Author>,
 	exclude_merges: bool,
+	first_parent: bool,
 	exclude_author: Option<String>,
-	target_branch: Option<String>,
+	exclude_authors: Vec<String>,
+	exclude_bots: bool,
+	scope: HistoryScope,
+	use_mailmap: bool,
+	max_stat_files: Option<u32>,
+	pickaxe: Option<String>,
+	pickaxe_regex: Option<String>,
+	max_count: Option<usize>,
+	exclude_paths: Vec<String>,
+	exclude_messages: Vec<String>,
+	author_since: Option<i64>,
+	author_until: Option<i64>,
+	renames_only: bool,
+	rename_threshold: Option<u8>,
+	exclude_generated: bool,
 }
 
 pub struct CommitArgsBuilder(CommitArgs);
 
-#[derive(Debug, Clone, Copy, Default, Serialize)]
+/// The commits walked by git, see [CommitArgsBuilder::history_scope]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HistoryScope {
+	/// The commits reachable from any ref (`--all`): all the local and remote branches, the tags and the stashes
+	#[default]
+	All,
+	/// Only the commits reachable from the current branch (`HEAD`)
+	Head,
+	/// Only the commits reachable from the given revision, e.g. a branch or a `from..to` range
+	Branch(String),
+}
+
+/// The authors excluded by [CommitArgsBuilder::exclude_bots], a Perl regular expression matched against the
+/// beginning of the `Name <email>` identity of the author:
+/// - any name ending with `[bot]`, e.g. `dependabot[bot]` or `github-actions[bot]`
+/// - the names starting with `dependabot`, `renovate`, `github-actions`, `GitHub Action`, `greenkeeper`,
+///   `snyk-bot` or `semantic-release-bot`
+pub const BOT_AUTHOR_PATTERN: &str =
+	r"[^<]*\[bot\] <|(?:dependabot|renovate|github-actions|GitHub Action|greenkeeper|snyk-bot|semantic-release-bot)";
+
+/// The files excluded by [CommitArgsBuilder::exclude_generated], in any directory: the lock files of the common
+/// package managers and the minified assets
+pub const GENERATED_FILE_PATTERNS: &[&str] = &[
+	"Cargo.lock",
+	"package-lock.json",
+	"yarn.lock",
+	"pnpm-lock.yaml",
+	"Gemfile.lock",
+	"composer.lock",
+	"poetry.lock",
+	"go.sum",
+	"*.min.js",
+	"*.min.css",
+	"*.js.map",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CommitStats {
-	pub files_changed: u32,
-	pub lines_added: u32,
-	pub lines_deleted: u32,
+	pub files_changed: u64,
+	pub lines_added: u64,
+	pub lines_deleted: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct CommitDetail {
 	pub hash: CommitHash,
 	pub author: Author,
 	pub author_timestamp: i64,
+	/// 0 for the commits serialized without it
+	#[serde(default)]
+	pub committer_timestamp: i64,
 	pub stats: CommitStats,
+	/// authors credited with a `Co-authored-by:` trailer
+	#[serde(default)]
+	pub co_authors: Vec<Author>,
+	/// the changed files, a renamed file has its new path
+	#[serde(default)]
+	pub files: Vec<FileStat>,
+	/// the renamed or moved files as `(old path, new path)`, see [CommitArgsBuilder::rename_threshold].
+	/// Unlike `files`, the renames are never truncated
+	#[serde(default)]
+	pub renames: Vec<(String, String)>,
+	/// true if the list of files has been capped by `CommitArgs::max_stat_files`
+	#[serde(default)]
+	pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStat {
+	pub path: String,
+	pub lines_added: u64,
+	pub lines_deleted: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct MinimalCommitDetail {
 	pub hash: CommitHash,
 	pub author_timestamp: i64,
 	pub stats: CommitStats,
+	/// the paths of the changed files
+	#[serde(default)]
+	pub files: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalStat {
 	pub author: Author,
 	pub commits_count: usize,
 	pub stats: CommitStats,
+	/// number of distinct files changed by the author, while `stats.files_changed` is the sum of the files
+	/// changed by each commit. 0 if the commits have no per-file stats
+	#[serde(default)]
+	pub distinct_files: usize,
+	/// timestamp of the first commit of the author
+	#[serde(default)]
+	pub first_commit: i64,
+	/// timestamp of the last commit of the author
+	#[serde(default)]
+	pub last_commit: i64,
+}
+
+/// Share of an author in the repository totals, as percentages (0 to 100),
+/// see [CommitsPerAuthor::global_stats_with_shares]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Shares {
+	pub commits_pct: f64,
+	pub lines_added_pct: f64,
+	pub lines_deleted_pct: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct SimpleStat {
 	pub commits_count: usize,
 	pub stats: CommitStats,
 }
 
+/// Sorting criteria for the global stats.
+/// Counters are sorted from the highest to the lowest, while `Name` and `Email` are sorted
+/// alphabetically (case-insensitive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SortStatsBy {
+	#[default]
 	Commits,
 	FilesChanged,
 	LinesAdded,
 	LinesDeleted,
+	Name,
+	Email,
+}
+
+/// How the `Co-authored-by:` co-authors are credited in [traits::CommitStatsExt::commits_per_author_with]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoAuthorMode {
+	/// Only the commit author is credited, co-authors are ignored
+	#[default]
+	Ignore,
+	/// The commit and its full stats are credited to the author and to each co-author,
+	/// so the stats of a co-authored commit are counted once per participant
+	Full,
+	/// The commit is credited to the author and to each co-author, but its stats are split evenly
+	/// among them (the remainder of the division goes to the commit author)
+	Split,
+}
+
+/// First day of the week of the weekday axis, see [CommitsHeatMap::global_stats_week_start]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekStart {
+	#[default]
+	Monday,
+	Sunday,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct CommitsPerAuthor(pub(crate) HashMap<Author, Vec<MinimalCommitDetail>>);
+/// The days of the weekend, see [CommitsPerWeekday::weekend_vs_weekday].
+/// The default weekend is Saturday and Sunday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weekend(Vec<Weekday>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+	Ascending,
+	Descending,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitsPerAuthor(
+	#[serde(with = "ser::ordered_map")] pub(crate) HashMap<Author, Vec<MinimalCommitDetail>>,
+);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitsPerWeekday(
+	#[serde(with = "ser::ordered_nested_map")] pub(crate) HashMap<u8, HashMap<Author, SimpleStat>>,
+);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitsPerDayHour(
+	#[serde(with = "ser::ordered_nested_map")] pub(crate) HashMap<u32, HashMap<Author, SimpleStat>>,
+);
 
-#[derive(Debug, Clone, Serialize)]
-pub struct CommitsPerWeekday(pub(crate) HashMap<u8, HashMap<Author, SimpleStat>>);
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitsPerMonth(
+	#[serde(with = "ser::ordered_nested_map")] pub(crate) HashMap<String, HashMap<Author, SimpleStat>>,
+);
 
-#[derive(Debug, Clone, Serialize)]
-pub struct CommitsPerDayHour(pub(crate) HashMap<u32, HashMap<Author, SimpleStat>>);
+/// Contains an hashmap where the key is the ISO week (e.g. `2024-W07`) and the value the stats per author
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitsPerWeek(
+	#[serde(with = "ser::ordered_nested_map")] pub(crate) HashMap<String, HashMap<Author, SimpleStat>>,
+);
 
-#[derive(Debug, Clone, Serialize)]
-pub struct CommitsPerMonth(pub(crate) HashMap<String, HashMap<Author, SimpleStat>>);
+/// Contains an hashmap where the key is the file extension (without the dot, or an empty string for the
+/// files without extension) and the value the stats of the files with that extension
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsPerExtension(
+	#[serde(with = "ser::ordered_map")] pub(crate) HashMap<String, SimpleStat>,
+);
 
 ///
 /// Contains an hashmap where the key is the Author and the value is a matrix[weekday, hour] of stats
-#[derive(Debug, Clone, Serialize)]
-pub struct CommitsHeatMap(pub(crate) HashMap<Author, Vec<Vec<SimpleStat>>>);
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitsHeatMap(
+	#[serde(with = "ser::ordered_map")] pub(crate) HashMap<Author, Vec<Vec<SimpleStat>>>,
+);
+
+/// Delay, in days, between the author and the committer dates of a set of commits.
+/// Commits with a committer date before the author date (clock skew) are not part of the stats,
+/// they're only counted in `negative_count`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LandingDelayStats {
+	/// number of commits used for the stats
+	pub commits_count: usize,
+	pub average_days: f64,
+	pub median_days: f64,
+	pub max_days: f64,
+	/// number of commits with a negative delay
+	pub negative_count: usize,
+}
+
+/// Activity of an author over time, see [CommitsPerAuthor::streaks].
+/// Days are calendar days in UTC: multiple commits on the same day count as a single active day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreakInfo {
+	/// the longest run of consecutive days with at least one commit
+	pub longest_consecutive_days: u32,
+	/// the run of consecutive days ending on the day of the most recent commit of the stats (of any author),
+	/// 0 if the author didn't commit on that day
+	pub current_streak: u32,
+	/// number of distinct days with at least one commit
+	pub active_days: u32,
+	/// timestamp of the first commit
+	pub first_commit: i64,
+	/// timestamp of the last commit
+	pub last_commit: i64,
+}
+
+/// Estimated churn of an author, see [traits::CommitStatsExt::churn_per_author]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Churn {
+	/// added lines in files that were not changed again within the window
+	pub productive_lines: u64,
+	/// added lines in files that were changed again within the window
+	pub churned_lines: u64,
+}
+
+/// The default thresholds of [traits::CommitStatsExt::size_distribution], for the buckets
+/// `0`, `1-9`, `10-49`, `50-199`, `200-999` and `1000+` changed lines
+pub const DEFAULT_SIZE_THRESHOLDS: [u64; 5] = [1, 10, 50, 200, 1000];
+
+/// A range of commit sizes, in changed lines (added plus deleted), see [SizeHistogram]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeBucket {
+	/// the smallest size of the bucket
+	pub min: u64,
+	/// the biggest size of the bucket (inclusive), `None` for the last bucket
+	pub max: Option<u64>,
+	pub commits_count: usize,
+}
+
+/// Number of commits per size, see [traits::CommitStatsExt::size_distribution]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SizeHistogram {
+	/// the buckets, from the smallest to the biggest commits
+	pub buckets: Vec<SizeBucket>,
+	/// the number of commits of each author in each bucket, in the same order of `buckets`
+	#[serde(with = "ser::ordered_map")]
+	pub per_author: HashMap<Author, Vec<usize>>,
+}
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Detail {
 	/// repository size in Kilobytes
 	pub size: u64,
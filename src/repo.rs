@@ -1,25 +1,46 @@
-use std::ffi::OsStr;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
 use std::fmt::{Display, Formatter};
-use std::io::BufRead;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Lines, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
 use lazy_static::lazy_static;
 use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
 use regex::Regex;
 use simple_cmd::{CommandBuilder, Vec8ToString};
 use which::which;
 
-use crate::{Author, CommitArgs, CommitDetail, CommitHash, CommitStats, Detail, Repo};
+use crate::impls::cmp_ignore_case;
+use crate::traits::CommitStatsExt;
+use crate::{
+	Author, AuthorSummary, CommitArgs, CommitDetail, CommitHash, CommitStats, Detail, Error, FileStat, HistoryScope,
+	MergeDiffMode, Remote, Repo, SortStatsBy, TagInterval, WorkTreeStatus,
+};
+
+/// Release name used by [Repo::commits_by_release] for the commits not contained in any tag
+const UNRELEASED: &str = "unreleased";
+
+/// `--pretty` format of a single commit: hash, author name, author email, author timestamp, committer timestamp
+/// and the `Co-authored-by` trailers separated by `0x1f`
+const COMMIT_FORMAT: &str = "%H%n%aN%n%aE%n%at%n%ct%n%(trailers:key=Co-authored-by,valueonly,separator=%x1f)";
 
 lazy_static! {
-	static ref SHORT_STATS_RE: Regex = regex::Regex::new("(?<files>[\\d]+) files? changed(, (?<insertions>[\\d]+) insertions?\\(\\+\\))?(, (?<deletions>[\\d]+) deletions?\\(\\-\\))?$").unwrap();
-	static ref NUMSTATS_RE: Regex = regex::Regex::new("^(?<additions>[\\d]+)\\s+(?<deletions>[\\d]+)\\s+(?<filename>[^\n]+)").unwrap();
+	static ref SHORT_STATS_RE: Regex = regex::Regex::new("^\\s*(?<files>[\\d]+) files? changed(, (?<insertions>[\\d]+) insertions?\\(\\+\\))?(, (?<deletions>[\\d]+) deletions?\\(\\-\\))?$").unwrap();
+	/// Binary files are reported with `-` instead of the number of lines
+	static ref NUMSTATS_RE: Regex = regex::Regex::new("^(?<additions>[\\d]+|-)\\t(?<deletions>[\\d]+|-)\\t(?<filename>[^\n]+)").unwrap();
 	static ref SIZE_RE: Regex = regex::RegexBuilder::new(r#"^size-pack:\s*(?<size>[\d]+)$"#).multi_line(true).build().unwrap();
 }
 
 impl Repo {
-	/// Create a new instance of a Repository
+	/// Create a new instance of a Repository.
+	/// The path is not validated: if it's not a git repository, the errors will only surface on the first git
+	/// operation. [Repo::open] is the recommended way to create a repository.
 	/// # Examples:
 	/// ```rust
 	/// use gitstats::Repo;
@@ -29,7 +50,115 @@ impl Repo {
 	/// }
 	/// ```
 	pub fn new<S: AsRef<OsStr> + ?Sized>(s: &S) -> Self {
-		Repo { inner: PathBuf::from(s) }
+		Repo {
+			inner: PathBuf::from(s),
+			timeout: None,
+			merge_diff_mode: MergeDiffMode::default(),
+			max_stat_files: None,
+			git_binary: None,
+		}
+	}
+
+	/// Create a new instance of a Repository, verifying that the path is inside a git repository.
+	/// Returns [Error::NotARepository] if the path is not a git repository or git cannot be found.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     match Repo::open("/custom/path/to/repo") {
+	///         Ok(repo) => println!("repository: {repo}"),
+	///         Err(err) => println!("Error: {err}"),
+	///     }
+	/// }
+	/// ```
+	pub fn open<S: AsRef<OsStr> + ?Sized>(s: &S) -> anyhow::Result<Self> {
+		let repo = Repo::new(s);
+		let not_a_repository = || Error::NotARepository(repo.inner.clone());
+		let command = repo.git().map_err(|_| not_a_repository())?.with_args(["rev-parse", "--is-inside-work-tree"]);
+		let output = repo.output(command)?;
+		if !output.status.success() {
+			return Err(not_a_repository().into());
+		}
+		Ok(repo)
+	}
+
+	/// Set the maximum time a single git command is allowed to run.
+	/// When the timeout expires, the git process is killed and the operation fails with [Error::Timeout].
+	/// By default git commands are never interrupted.
+	/// Note that the timeout is not applied to the iterator returned by [Repo::list_commits_iter].
+	/// # Examples:
+	/// ```rust
+	/// use std::time::Duration;
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo").with_timeout(Duration::from_secs(30));
+	///     if let Err(err) = repo.fetch() {
+	///         println!("Error: {err}");
+	///     }
+	/// }
+	/// ```
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Set how the stats of the merge commits are computed by [Repo::commit_stats], [Repo::commits_stats] and
+	/// [Repo::list_commit_details]. By default merge commits have empty stats ([MergeDiffMode::Off]).
+	/// The combined diff of a merge (the conflict resolutions) is available with [Repo::merge_commit_stats].
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, MergeDiffMode, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo").with_merge_diff_mode(MergeDiffMode::FirstParent);
+	///     if let Ok(commits) = repo.list_commit_details(CommitArgs::default()) {
+	///         println!("{} commits", commits.len());
+	///     }
+	/// }
+	/// ```
+	pub fn with_merge_diff_mode(mut self, mode: MergeDiffMode) -> Self {
+		self.merge_diff_mode = mode;
+		self
+	}
+
+	/// Limit the number of files collected for each commit by [Repo::commit_stats], [Repo::commits_stats] and
+	/// [Repo::commit_stats_many], like [crate::CommitArgsBuilder::max_stat_files] does for the commits listed
+	/// with [CommitArgs]. The files after the first `value` are skipped and the commit is marked as `truncated`,
+	/// the commit stats are still complete.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo").with_max_stat_files(1000);
+	///     if let Ok(commits) = repo.list_commits(CommitArgs::default()) {
+	///         let stats = repo.commits_stats(&commits);
+	///     }
+	/// }
+	/// ```
+	pub fn with_max_stat_files(mut self, value: u32) -> Self {
+		self.max_stat_files = Some(value);
+		self
+	}
+
+	/// Use the given git executable, instead of searching `git` in the `PATH`.
+	/// Useful when git is not in the `PATH` or there are multiple installations of git.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo").with_git_binary("/opt/git/bin/git".into());
+	///     if let Ok(count) = repo.commits_count() {
+	///         println!("{count} commits");
+	///     }
+	/// }
+	/// ```
+	pub fn with_git_binary(mut self, path: PathBuf) -> Self {
+		self.git_binary = Some(path);
+		self
 	}
 
 	pub fn to_str(&self) -> Option<&str> {
@@ -51,24 +180,102 @@ impl Repo {
 	/// }
 	/// ```
 	pub fn fetch(&self) -> anyhow::Result<()> {
-		self.git()?
-			.arg("fetch")
-			.build()
-			.output()
-			.map(|_| ())
-			.context("Failed to fetch remote")
+		let command = self.git()?.arg("fetch");
+		self.output(command).map(|_| ()).context("Failed to fetch remote")
 	}
 
 	/// Fetch all the remotes
 	pub fn fetch_all(&self) -> anyhow::Result<()> {
-		self.git()?
-			.args([
-				"fetch", "--all",
-			])
-			.build()
-			.output()
-			.map(|_| ())
-			.context("Failed to fetch remotes")
+		let command = self.git()?.args([
+			"fetch", "--all",
+		]);
+		self.output(command).map(|_| ()).context("Failed to fetch remotes")
+	}
+
+	/// Fetch the given remote, optionally removing the remote-tracking branches that no longer exist on the
+	/// remote (`--prune`), so that they don't show up in the stats of all the branches.
+	/// On failure the error contains the git error message.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Err(err) = repo.fetch_remote("upstream", true) {
+	///         println!("Error: {err}");
+	///     }
+	/// }
+	/// ```
+	pub fn fetch_remote(&self, remote: &str, prune: bool) -> anyhow::Result<()> {
+		let mut command = self.git()?.with_args(["fetch", remote]);
+		if prune {
+			command = command.with_arg("--prune");
+		}
+		let output = self.output(command)?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("failed to fetch remote `{remote}`: {}", stderr.trim()));
+		}
+		Ok(())
+	}
+
+	/// Returns the default branch of the repository, to be used as [crate::CommitArgsBuilder::target_branch]
+	/// instead of all the branches.
+	/// The default branch is the one the `origin` remote points to (`refs/remotes/origin/HEAD`, e.g. `origin/main`,
+	/// set when the repository is cloned or with `git remote set-head origin --auto`). Otherwise it's the local
+	/// `main` or `master` branch, if any. The remote is not contacted.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(Some(branch)) = repo.default_branch() {
+	///         let args = CommitArgs::builder().target_branch(&branch).build().unwrap();
+	///         println!("{:?}", repo.list_commits(args));
+	///     }
+	/// }
+	/// ```
+	pub fn default_branch(&self) -> anyhow::Result<Option<String>> {
+		let command = self.git()?.with_args(["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"]);
+		let output = self.output(command)?;
+		if output.status.success() {
+			let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+			if !branch.is_empty() {
+				return Ok(Some(branch));
+			}
+		}
+
+		for branch in ["main", "master"] {
+			let command = self.git()?.with_args(["show-ref", "--verify", "--quiet", &format!("refs/heads/{branch}")]);
+			if self.output(command)?.status.success() {
+				return Ok(Some(branch.to_string()));
+			}
+		}
+		Ok(None)
+	}
+
+	/// Returns the remotes of the repository, with their fetch and push urls, in the order listed by `git remote`
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(remotes) = repo.list_remotes() {
+	///         for remote in remotes {
+	///             println!("{}: {}", remote.name, remote.fetch_url);
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn list_remotes(&self) -> anyhow::Result<Vec<Remote>> {
+		let command = self.git()?.with_args(["remote", "-v"]);
+		let output = self.output(command)?;
+		if !output.status.success() {
+			return Err(anyhow!("failed to list the remotes"));
+		}
+		Ok(parse_remotes(&String::from_utf8_lossy(&output.stdout)))
 	}
 
 	/// Returns a list of commits based on the input arguments
@@ -82,30 +289,249 @@ impl Repo {
 	///     let repo = Repo::from(&repo_dir);
 	/// 	let commit_args = CommitArgs::default();
 	///     match repo.list_commits(commit_args) {
-	///         Ok(commits) => println!("got commits: {commits}"),
+	///         Ok(commits) => println!("got commits: {commits:?}"),
 	///         Err(err) => println!("Error: {err}"),
 	///     }
 	/// }
 	/// ```
 	pub fn list_commits(&self, options: CommitArgs) -> anyhow::Result<Vec<CommitHash>> {
+		let command = self.list_commits_command(&options)?;
+		let output = self.output(command)?;
+		Ok(parse_commit_list(&output.stdout, &options))
+	}
+
+	/// The `git log` command of [Repo::list_commits], whose output is parsed by [parse_commit_list]
+	fn list_commits_command(&self, options: &CommitArgs) -> anyhow::Result<CommandBuilder> {
 		options.validate()?;
-		let mut command = self.git()?.arg("log");
-		command = command.with_args(options).with_arg("--reverse");
-		let output = command.build().output()?;
-		Ok(output
-			.stdout
+		let args = options.to_git_args();
+		tracing::debug!(?args, "listing the commits of {:?}", self.inner);
+		Ok(self.git()?.with_args(["log", "--reverse", "--pretty=%H %at"]).with_args(args))
+	}
+
+	/// Returns the commits reachable from `to` but not from `from` (`git log from..to`) matching the input arguments,
+	/// e.g. all the commits between two release tags.
+	/// The range replaces the [crate::HistoryScope] of the arguments. Both refs must resolve to a commit.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     match repo.commits_between("v1.0", "v1.1", CommitArgs::default()) {
+	///         Ok(commits) => println!("{} commits in v1.1", commits.len()),
+	///         Err(err) => println!("Error: {err}"),
+	///     }
+	/// }
+	/// ```
+	pub fn commits_between(&self, from: &str, to: &str, args: CommitArgs) -> anyhow::Result<Vec<CommitHash>> {
+		let from = self.resolve_ref(from)?;
+		let to = self.resolve_ref(to)?;
+		self.list_commits(CommitArgs {
+			scope: HistoryScope::Branch(format!("{from}..{to}")),
+			..args
+		})
+	}
+
+	/// Returns a lazy iterator over the commits matching the input arguments.
+	/// The hashes are read from `git log` as they are produced, so the whole history is never held in memory.
+	/// Unlike [Repo::list_commits], the commits are returned from the newest to the oldest (`--reverse` would force
+	/// git to buffer the entire log before producing any output).
+	/// The git process is kept alive by the iterator, and killed when the iterator is dropped.
+	/// If git fails (e.g. an unknown branch), the last item is an error with the git message.
+	///
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.list_commits_iter(CommitArgs::default()) {
+	///         for commit in commits.take(10).flatten() {
+	///             println!("commit: {commit}");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn list_commits_iter(&self, options: CommitArgs) -> anyhow::Result<impl Iterator<Item = anyhow::Result<CommitHash>>> {
+		options.validate()?;
+		let command = self.git()?.with_args(["log", "--pretty=%H %at"]).with_args(options.clone());
+		let lines = ChildLines::spawn(command)?;
+		Ok(lines.filter_map(move |line| match line {
+			Ok(line) => hash_in_author_date_range(&line, &options).map(Ok),
+			Err(err) => Some(Err(err)),
+		}))
+	}
+
+	/// Expand an abbreviated hash, or any other reference, to the full hash of the commit (`git rev-parse`).
+	/// Same as [Repo::resolve_ref].
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     match repo.resolve("a9ae91e") {
+	///         Ok(hash) => println!("full hash: {hash}, short: {}", hash.short(7)),
+	///         Err(err) => println!("Error: {err}"),
+	///     }
+	/// }
+	/// ```
+	pub fn resolve(&self, rev: &str) -> anyhow::Result<CommitHash> {
+		self.resolve_ref(rev)
+	}
+
+	/// Resolve a reference (branch, tag, short hash...) to the full hash of the commit it points to
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     match repo.resolve_ref("v1.0") {
+	///         Ok(hash) => println!("v1.0 is {hash}"),
+	///         Err(err) => println!("Error: {err}"),
+	///     }
+	/// }
+	/// ```
+	pub fn resolve_ref(&self, rev: &str) -> anyhow::Result<CommitHash> {
+		let command = self.git()?.with_args([
+			"rev-parse".to_string(),
+			"--verify".to_string(),
+			"--quiet".to_string(),
+			format!("{rev}^{{commit}}"),
+		]);
+		let output = self.output(command)?;
+		let hash = output.stdout.as_str().map(|s| s.trim()).unwrap_or_default();
+		if !output.status.success() || hash.is_empty() {
+			return Err(anyhow!("cannot resolve ref `{rev}`"));
+		}
+		Ok(CommitHash(hash.to_string()))
+	}
+
+	/// Returns true if the given hash (or any other reference) is a commit of the repository
+	/// (`git cat-file -e <hash>^{commit}`)
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitHash, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     let hash = CommitHash::try_from("a9ae91e").unwrap();
+	///     if let Ok(false) = repo.commit_exists(&hash) {
+	///         println!("{hash} is not a commit");
+	///     }
+	/// }
+	/// ```
+	pub fn commit_exists(&self, hash: &CommitHash) -> anyhow::Result<bool> {
+		let command = self.git()?.with_args(["cat-file".to_string(), "-e".to_string(), format!("{hash}^{{commit}}")]);
+		Ok(self.output(command)?.status.success())
+	}
+
+	/// Group the commits matching the input arguments by the release tag that first contains them.
+	/// Tags are walked from the oldest to the newest (by creation date) and each commit is assigned to the
+	/// earliest tag it is reachable from. Commits not contained in any tag are grouped under the `"unreleased"` key.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(releases) = repo.commits_by_release(CommitArgs::default()) {
+	///         for (release, commits) in releases {
+	///             println!("{release}: {} commits", commits.len());
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn commits_by_release(&self, args: CommitArgs) -> anyhow::Result<HashMap<String, Vec<CommitHash>>> {
+		let commits = self.list_commits(args)?;
+		let mut release_of: HashMap<String, String> = HashMap::new();
+		let mut previous_tags: Vec<String> = Vec::new();
+
+		for tag in self.release_tags()? {
+			let command = self
+				.git()?
+				.with_debug(false)
+				.with_args(["rev-list", tag.as_str(), "--not"])
+				.with_args(&previous_tags);
+			let output = self.output(command)?;
+			if !output.status.success() {
+				return Err(anyhow!("failed to list the commits of `{tag}`"));
+			}
+			let name = tag.strip_prefix("refs/tags/").unwrap_or(&tag).to_string();
+			for line in output.stdout.lines() {
+				release_of.entry(line?).or_insert_with(|| name.clone());
+			}
+			previous_tags.push(tag);
+		}
+
+		let mut result: HashMap<String, Vec<CommitHash>> = HashMap::new();
+		for commit in commits {
+			let release = release_of.get(&commit.0).map_or(UNRELEASED, |name| name.as_str());
+			result.entry(release.to_string()).or_default().push(commit);
+		}
+		Ok(result)
+	}
+
+	/// Returns the intervals between consecutive tags, sorted by the date of the tags (see [TagInterval]).
+	/// The tags are ordered by their creation date: the tagger date of annotated tags, the committer date of the
+	/// tagged commit otherwise. Returns an empty list if the repository has fewer than two tags.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(intervals) = repo.tag_cadence() {
+	///         for interval in intervals {
+	///             let (from, to) = (&interval.from, &interval.to);
+	///             println!("{from} -> {to}: {:.1} days, {} commits", interval.days, interval.commits);
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn tag_cadence(&self) -> anyhow::Result<Vec<TagInterval>> {
+		let command = self.git()?.with_args([
+			"for-each-ref",
+			"--sort=creatordate",
+			"--format=%(refname)%00%(creatordate:unix)",
+			"refs/tags",
+		]);
+		let output = self.output(command)?;
+		if !output.status.success() {
+			return Err(anyhow!("failed to list the tags"));
+		}
+		let tags = String::from_utf8_lossy(&output.stdout)
 			.lines()
-			.filter_map(|line| if let Ok(line) = line { Some(CommitHash(line)) } else { None })
-			.collect::<Vec<_>>())
+			.filter_map(|line| line.split_once('\0'))
+			.map(|(tag, date)| {
+				let date = date.parse::<i64>().with_context(|| format!("invalid date of `{tag}`"))?;
+				Ok((tag.to_string(), date))
+			})
+			.collect::<anyhow::Result<Vec<_>>>()?;
+
+		tags.windows(2)
+			.map(|pair| {
+				let ((from, from_date), (to, to_date)) = (&pair[0], &pair[1]);
+				let commits = self.commits_between(from, to, CommitArgs::default())?.len();
+				let name = |tag: &str| tag.strip_prefix("refs/tags/").unwrap_or(tag).to_string();
+				Ok(TagInterval {
+					from: name(from),
+					to: name(to),
+					days: (to_date - from_date) as f64 / 86_400.0,
+					commits,
+				})
+			})
+			.collect()
 	}
 
 	pub fn first_commit(&self) -> anyhow::Result<Option<CommitDetail>> {
-		let command = self.git()?.with_args(&[
+		let command = self.git()?.with_args([
 			"rev-list",
 			"--max-parents=0",
 			"HEAD",
 		]);
-		let output = command.build().output()?;
+		let output = self.output(command)?;
 		if let Some(commit) = output.stdout.as_str().map(|line| CommitHash(line.trim().to_string())) {
 			Ok(Some(self.commit_stats(commit)?))
 		} else {
@@ -114,10 +540,10 @@ impl Repo {
 	}
 
 	pub fn last_commit(&self) -> anyhow::Result<Option<CommitDetail>> {
-		let command = self.git()?.with_args(&[
+		let command = self.git()?.with_args([
 			"rev-list", "-n", "1", "HEAD",
 		]);
-		let output = command.build().output()?;
+		let output = self.output(command)?;
 		if let Some(commit) = output.stdout.as_str().map(|line| CommitHash(line.trim().to_string())) {
 			Ok(Some(self.commit_stats(commit)?))
 		} else {
@@ -127,11 +553,11 @@ impl Repo {
 
 	/// Return the repository size (in Kilobytes)
 	pub fn size(&self) -> anyhow::Result<u64> {
-		let command = self.git()?.with_args(&[
+		let command = self.git()?.with_args([
 			"count-objects",
 			"-v",
 		]);
-		let output = command.build().output()?;
+		let output = self.output(command)?;
 		let string = output
 			.stdout
 			.as_str()
@@ -146,29 +572,89 @@ impl Repo {
 		}
 	}
 
+	/// Returns true if the working tree has uncommitted changes, including the untracked files: the stats only
+	/// reflect the committed history.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(true) = repo.is_dirty() {
+	///         println!("warning: the uncommitted changes are not part of the stats");
+	///     }
+	/// }
+	/// ```
+	pub fn is_dirty(&self) -> anyhow::Result<bool> {
+		Ok(!self.status_porcelain()?.trim().is_empty())
+	}
+
+	/// Returns the number of staged, modified, untracked and conflicted files of the working tree
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(status) = repo.status() {
+	///         println!("{} staged, {} modified, {} untracked", status.staged, status.modified, status.untracked);
+	///     }
+	/// }
+	/// ```
+	pub fn status(&self) -> anyhow::Result<WorkTreeStatus> {
+		Ok(parse_status(&self.status_porcelain()?))
+	}
+
+	/// Returns the output of `git status --porcelain`
+	fn status_porcelain(&self) -> anyhow::Result<String> {
+		let command = self.git()?.with_args(["status", "--porcelain"]);
+		let output = self.output(command)?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("failed to read the status of the working tree: {}", stderr.trim()));
+		}
+		Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+	}
+
 	/// Returns the total commits
 	pub fn commits_count(&self) -> anyhow::Result<usize> {
-		let command = self.git()?.with_args(&[
+		let command = self.git()?.with_args([
 			"rev-list", "--count", "--all",
 		]);
-		let output = command.build().output()?;
+		let output = self.output(command)?;
 		let string = output.stdout.lines().nth(0).ok_or(anyhow!("failed to get total commits"))??;
 		Ok(string.parse::<usize>()?)
 	}
 
 	pub fn details(&self) -> anyhow::Result<Detail> {
 		let size = self.size()?;
-		let first_commit = self.first_commit()?;
-		let last_commit = self.last_commit()?;
+		let (first_commit, last_commit) = self.first_and_last_commit_timestamps()?;
 		let commits_count = self.commits_count()?;
 		Ok(Detail {
 			size,
 			commits_count,
-			first_commit: first_commit.map(|c| c.author_timestamp),
-			last_commit: last_commit.map(|c| c.author_timestamp),
+			first_commit,
+			last_commit,
 		})
 	}
 
+	/// Returns the author timestamps of [Repo::first_commit] and [Repo::last_commit], reading only the commit
+	/// timestamps and parents of the `HEAD` history with a single git command
+	fn first_and_last_commit_timestamps(&self) -> anyhow::Result<(Option<i64>, Option<i64>)> {
+		let command = self.git()?.with_debug(false).with_args(["log", "--format=%at %P", "HEAD"]);
+		let output = self.output(command)?;
+		if !output.status.success() {
+			return Ok((None, None));
+		}
+
+		let output = String::from_utf8_lossy(&output.stdout);
+		let parse = |line: &str| line.split(' ').next().and_then(|timestamp| timestamp.parse::<i64>().ok());
+		let last_commit = output.lines().next().and_then(parse);
+		// the root commits have no parents, the first one listed is the same returned by `--max-parents=0`
+		let first_commit = output.lines().find(|line| line.trim_end().split(' ').count() == 1).and_then(parse);
+		Ok((first_commit, last_commit))
+	}
+
 	/// Extract details from a list of commits
 	/// # Examples:
 	/// ```rust
@@ -187,94 +673,1351 @@ impl Repo {
 	///
 	/// ```
 	pub fn commits_stats(&self, commits: &Vec<CommitHash>) -> anyhow::Result<Vec<CommitDetail>> {
+		self.commits_stats_with_progress(commits, |_, _| {})
+	}
+
+	/// Same as [Repo::commits_stats], but `on_progress` is invoked with `(done, total)` every time a commit
+	/// has been processed. Since the commits are processed in parallel, the callback can be invoked from
+	/// different threads and the notifications are not guaranteed to arrive in order.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.list_commits(CommitArgs::default()) {
+	///         let stats = repo.commits_stats_with_progress(&commits, |done, total| {
+	///             println!("{done}/{total}");
+	///         });
+	///     }
+	/// }
+	/// ```
+	pub fn commits_stats_with_progress(
+		&self,
+		commits: &Vec<CommitHash>,
+		on_progress: impl Fn(usize, usize) + Sync,
+	) -> anyhow::Result<Vec<CommitDetail>> {
+		let total = commits.len();
+		let done = AtomicUsize::new(0);
 		commits
 			.into_par_iter()
-			.map(|commit| self.commit_stats(commit.to_owned()))
+			.map(|commit| {
+				let result = self.commit_stats(commit.to_owned());
+				on_progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+				result
+			})
 			.collect()
 	}
 
-	/// Extract details from a commit hash
+	/// Same as [Repo::commits_stats], but the commits are processed in a dedicated thread pool of `threads`
+	/// threads, limiting the number of `git` processes running at the same time.
+	/// A value of `0` means "use the rayon default" (the number of logical CPUs, unless configured
+	/// with `RAYON_NUM_THREADS`).
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.list_commits(CommitArgs::default()) {
+	///         let stats = repo.commits_stats_with_threads(&commits, 2);
+	///     }
+	/// }
+	/// ```
+	pub fn commits_stats_with_threads(
+		&self,
+		commits: &Vec<CommitHash>,
+		threads: usize,
+	) -> anyhow::Result<Vec<CommitDetail>> {
+		let pool = ThreadPoolBuilder::new()
+			.num_threads(threads)
+			.build()
+			.context("failed to create the thread pool")?;
+		pool.install(|| self.commits_stats(commits))
+	}
+
+	/// Extract details from a commit hash.
+	/// Fails with [Error::UnknownCommit] if the hash is not found in the repository, see [Repo::commit_exists]
 	pub fn commit_stats(&self, commit: CommitHash) -> anyhow::Result<CommitDetail> {
-		let mut command = self.git()?.with_debug(false);
 		let hash: &str = (&commit).into();
+		let command = self.show_details_command([hash])?;
 
-		command = command
-			.arg("show")
-			.arg("--shortstat")
-			.arg("--pretty=\"format:%H\n%aN\n%aE\n%at\n\"")
-			.arg(hash);
-
-		let result = command.build().output()?;
-		let output = result.stdout;
-		let lines = output.lines().map(|f| f.unwrap()).collect::<Vec<String>>();
-		let size = lines.len();
-
-		let mut commit_hash: Option<String> = None;
-		let mut author_name: Option<String> = None;
-		let mut author_email: Option<String> = None;
-		let mut author_date: Option<i64> = None;
-
-		for index in 0..size {
-			let line = &lines[index];
-
-			match index {
-				0 => commit_hash = Some(line.to_string()),
-				1 => author_name = Some(line.to_string()),
-				2 => author_email = Some(line.to_string()),
-				3 => {
-					let timestamp = line.parse::<i64>().expect("invalid timestamp");
-					author_date = Some(timestamp);
-				}
-				_ => {
-					// unexpected
-				}
+		let result = self.output(command)?;
+		if !result.status.success() {
+			// tell an unknown hash apart from the other failures, without checking every commit beforehand
+			if !self.commit_exists(&commit)? {
+				return Err(Error::UnknownCommit(commit.to_string()).into());
 			}
+			let stderr = String::from_utf8_lossy(&result.stderr);
+			return Err(anyhow!("failed to read the stats of `{commit}`: {}", stderr.trim()));
 		}
+		let output = String::from_utf8_lossy(&result.stdout);
+		let commit_detail = parse_commit_details(&output, self.max_stat_files)?
+			.into_iter()
+			.next()
+			.ok_or_else(|| anyhow!("commit `{commit}` not found"))?;
 
-		let mut files: u32 = 0;
-		let mut insertions: u32 = 0;
-		let mut deletions: u32 = 0;
+		Ok(CommitDetail {
+			hash: commit,
+			..commit_detail
+		})
+	}
 
-		if let Some(find) = SHORT_STATS_RE.captures(lines.last().ok_or(anyhow!("failed to find last line"))?.as_str()) {
-			files = find.name("files").map_or(0, |f| f.as_str().parse::<u32>().unwrap_or(0));
-			insertions = find.name("insertions").map_or(0, |f| f.as_str().parse::<u32>().unwrap_or(0));
-			deletions = find.name("deletions").map_or(0, |f| f.as_str().parse::<u32>().unwrap_or(0));
+	/// Same as [Repo::commit_stats] for each of the given commits, in the same order, with a single `git show` for
+	/// all of them (or one every 256 commits) instead of a git process per commit.
+	/// Fails if any of the commits can't be found.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitHash, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     let hashes = ["a9ae91e", "3f1c2b7"].map(|hash| CommitHash::try_from(hash).unwrap());
+	///     if let Ok(commits) = repo.commit_stats_many(&hashes) {
+	///         for commit in commits {
+	///             println!("{commit}");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn commit_stats_many(&self, hashes: &[CommitHash]) -> anyhow::Result<Vec<CommitDetail>> {
+		let mut result = Vec::with_capacity(hashes.len());
+		for chunk in hashes.chunks(SHOW_CHUNK) {
+			let command = self.show_details_command(chunk.iter().map(|commit| commit.0.as_str()))?;
+			result.extend(parse_shown_commits(chunk, self.output(command)?, self.max_stat_files)?);
 		}
+		Ok(result)
+	}
 
-		if commit_hash.is_none() {
-			return Err(anyhow!("commit hash not found"));
-		} else if author_name.is_none() {
-			return Err(anyhow!("author name not found"));
-		} else if author_email.is_none() {
-			return Err(anyhow!("author email not found"));
-		} else if author_date.is_none() {
-			return Err(anyhow!("author datetime not found"));
+	/// Returns the stats of the combined diff (`git show --cc`) of a merge commit.
+	/// Unlike the diff against the parents (see [MergeDiffMode]), the combined diff only contains the changes
+	/// made during the merge itself (e.g. conflict resolutions): a trivial merge has empty stats.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(merge) = repo.resolve_ref("HEAD") {
+	///         match repo.merge_commit_stats(&merge) {
+	///             Ok(stats) => println!("conflict resolution: {stats}"),
+	///             Err(err) => println!("Error: {err}"),
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn merge_commit_stats(&self, hash: &CommitHash) -> anyhow::Result<CommitStats> {
+		let command = self
+			.git()?
+			.with_debug(false)
+			.with_args(["show", "--cc", "--pretty=format:", hash.into()]);
+		let output = self.output(command)?;
+		if !output.status.success() {
+			return Err(anyhow!("failed to show the commit `{hash}`"));
 		}
-
-		let stats = CommitStats {
-			files_changed: files,
-			lines_added: insertions,
-			lines_deleted: deletions,
-		};
-
-		let commit = CommitDetail {
-			hash: commit,
-			author: Author::new(author_name.unwrap()).with_email_opt(author_email.as_deref()),
-			author_timestamp: author_date.unwrap(),
-			stats,
-		};
-
-		Ok(commit)
+		Ok(parse_combined_diff(&String::from_utf8_lossy(&output.stdout)))
 	}
 
-	/// Will panic is git is not found
-	fn git(&self) -> anyhow::Result<CommandBuilder> {
-		let git = which("git")?;
-		//Ok(CommandBuilder::new(git).current_dir(&self.inner).with_debug(true))
+	/// Returns the stats of the diff between two refs, or between a ref and the working tree when `to` is `None`
+	/// (e.g. the size of the pending changes with `diff_stats("HEAD", None)`).
+	/// Untracked files are not included in the working tree diff.
+	/// This is the net change between the two points (e.g. how much changed in a release), computed with a single
+	/// `git diff --shortstat` without walking the commits: a line added by a commit and removed by a later one is
+	/// not counted, unlike in the sum of the [Repo::list_commit_details] stats.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     match repo.diff_stats("v1.0", Some("v1.1")) {
+	///         Ok(stats) => println!("v1.0..v1.1: {stats}"),
+	///         Err(err) => println!("Error: {err}"),
+	///     }
+	/// }
+	/// ```
+	pub fn diff_stats(&self, from: &str, to: Option<&str>) -> anyhow::Result<CommitStats> {
+		let mut command = self.git()?.with_debug(false).with_args(["diff", "--shortstat"]);
+		command = command.with_arg(self.resolve_ref(from)?.to_string());
+		if let Some(to) = to {
+			command = command.with_arg(self.resolve_ref(to)?.to_string());
+		}
+		let output = self.output(command)?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("failed to diff `{from}`: {}", stderr.trim()));
+		}
+		let output = String::from_utf8_lossy(&output.stdout);
+		Ok(output.lines().find_map(parse_short_stats).unwrap_or_default())
+	}
+
+	/// Returns the commits, with their stats, matching the input arguments.
+	/// Unlike [Repo::list_commits] followed by [Repo::commits_stats], the whole history is read
+	/// with a single `git log` invocation.
+	///
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     let args = CommitArgs::builder().max_stat_files(1000).build().unwrap();
+	///     if let Ok(commits) = repo.list_commit_details(args) {
+	///         for commit in commits.iter().filter(|c| c.truncated) {
+	///             println!("huge commit: {commit}");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn list_commit_details(&self, options: CommitArgs) -> anyhow::Result<Vec<CommitDetail>> {
+		options.validate()?;
+		let max_stat_files = options.max_stat_files;
+		let args = std::iter::once(OsString::from("--reverse")).chain(options.clone());
+		let mut commits = self.log_commit_details(args, max_stat_files)?;
+		commits.retain(|commit| options.matches_author_date(commit.author_timestamp));
+		Ok(commits)
+	}
+
+	/// Returns the last `n` commits of the current branch (HEAD), from the newest to the oldest, with their stats.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.recent(10) {
+	///         for commit in commits {
+	///             println!("{commit}");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn recent(&self, n: usize) -> anyhow::Result<Vec<CommitDetail>> {
+		self.log_commit_details(["HEAD".to_string(), format!("--max-count={n}")], None)
+	}
+
+	/// Run `git log` with the given arguments, reading the commit details in a single call.
+	/// The arguments are added last, so they can end with a pathspec
+	fn log_commit_details<I, S>(&self, args: I, max_stat_files: Option<u32>) -> anyhow::Result<Vec<CommitDetail>>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<OsStr>,
+	{
+		let output = self.output(self.log_details_command(args)?)?;
+		parse_commit_details(&String::from_utf8_lossy(&output.stdout), max_stat_files)
+	}
+
+	/// Returns the `git log` command printing the details of the commits, to be parsed with
+	/// [parse_commit_details] or [CommitDetails]
+	fn log_details_command<I, S>(&self, args: I) -> anyhow::Result<CommandBuilder>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<OsStr>,
+	{
+		Ok(self
+			.git()?
+			.with_debug(false)
+			.with_args([
+				"log".to_string(),
+				"--numstat".to_string(),
+				"--shortstat".to_string(),
+				"--find-renames".to_string(),
+				format!("--pretty=format:%x1e{COMMIT_FORMAT}"),
+				self.merge_diff_mode.as_arg().to_string(),
+			])
+			.with_args(args))
+	}
+
+	/// Returns the `git show` command printing the details of the given commits, in the same format of
+	/// [Repo::log_details_command]
+	fn show_details_command<I, S>(&self, revs: I) -> anyhow::Result<CommandBuilder>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<OsStr>,
+	{
+		Ok(self
+			.git()?
+			.with_debug(false)
+			.with_args([
+				"show".to_string(),
+				"--numstat".to_string(),
+				"--shortstat".to_string(),
+				"--find-renames".to_string(),
+				format!("--pretty=format:%x1e{COMMIT_FORMAT}"),
+				self.merge_diff_mode.as_arg().to_string(),
+			])
+			.with_args(revs))
+	}
+
+	/// Returns a lazy iterator over the commits matching the input arguments, with their stats: the streaming
+	/// counterpart of [Repo::list_commit_details], for the histories too big to be held in memory.
+	/// The commits are parsed from a single `git log` as it runs, and each one is returned as soon as its record
+	/// is complete. As for [Repo::list_commits_iter], the commits are returned from the newest to the oldest,
+	/// and the git process is killed when the iterator is dropped. If git fails, the last item is an error.
+	/// Note that the timeout set with [Repo::with_timeout] is not applied.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.commit_details_iter(CommitArgs::default()) {
+	///         let lines_added = commits.flatten().map(|commit| commit.stats.lines_added).sum::<u64>();
+	///         println!("{lines_added} lines added");
+	///     }
+	/// }
+	/// ```
+	pub fn commit_details_iter(
+		&self,
+		options: CommitArgs,
+	) -> anyhow::Result<impl Iterator<Item = anyhow::Result<CommitDetail>>> {
+		options.validate()?;
+		let lines = ChildLines::spawn(self.log_details_command(options.clone())?)?;
+		Ok(CommitDetails::new(lines, options.max_stat_files).filter(move |commit| match commit {
+			Ok(commit) => options.matches_author_date(commit.author_timestamp),
+			Err(_) => true,
+		}))
+	}
+
+	/// Write the commits matching the input arguments, with their stats, to `writer` as JSON lines (NDJSON):
+	/// one serialized [CommitDetail] per line.
+	/// The commits are written as they are read from `git log`, from the newest to the oldest, without holding the
+	/// whole history in memory. Returns the number of commits written.
+	/// Note that the timeout set with [Repo::with_timeout] is not applied.
+	/// # Examples:
+	/// ```rust
+	/// use std::io::stdout;
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Err(err) = repo.stream_commit_details_json(CommitArgs::default(), stdout().lock()) {
+	///         println!("Error: {err}");
+	///     }
+	/// }
+	/// ```
+	pub fn stream_commit_details_json(&self, options: CommitArgs, mut writer: impl Write) -> anyhow::Result<usize> {
+		let mut count = 0;
+		for commit in self.commit_details_iter(options)? {
+			serde_json::to_writer(&mut writer, &commit?)?;
+			writer.write_all(b"\n")?;
+			count += 1;
+		}
+		writer.flush()?;
+		Ok(count)
+	}
+
+	/// Returns the commits, matching the input arguments, that changed the given file, from the oldest to the
+	/// newest. The stats of each commit only count the lines of that file.
+	/// The path is relative to the root of the repository. With `follow` the history continues across renames
+	/// (`git log --follow`), otherwise it stops at the commit that created the file with its current name.
+	/// Note that git only follows the renames made by the commits matching the arguments (e.g. the author).
+	/// The arguments can't contain excluded paths (or generated files), as git can only follow a single path.
+	/// # Examples:
+	/// ```rust
+	/// use std::path::Path;
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.file_history(Path::new("src/lib.rs"), CommitArgs::default(), true) {
+	///         for commit in commits {
+	///             println!("{}: {}", commit.author, commit.stats);
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn file_history(&self, path: &Path, options: CommitArgs, follow: bool) -> anyhow::Result<Vec<CommitDetail>> {
+		options.validate()?;
+		if !options.excluded_pathspecs().is_empty() {
+			return Err(anyhow!("cannot exclude paths from the history of a single file"));
+		}
+		let max_stat_files = options.max_stat_files;
+		let mut args: Vec<OsString> = Vec::new();
+		if follow {
+			args.push("--follow".into());
+		}
+		args.extend(options.clone());
+		args.push("--".into());
+		args.push(path.into());
+
+		// `--reverse` doesn't play well with `--follow`, the order is reversed here instead
+		let mut commits = self.log_commit_details(args, max_stat_files)?;
+		commits.retain(|commit| options.matches_author_date(commit.author_timestamp));
+		commits.reverse();
+		Ok(commits)
+	}
+
+	/// Returns the number of lines of the files at HEAD, per file extension (without the dot, or an empty string for
+	/// the files without extension).
+	/// The committed version of the files is read (`git grep`, which already spreads the work on multiple threads),
+	/// so the uncommitted changes are not counted. Binary files are skipped.
+	/// Only the excluded paths of the arguments are used, see [crate::CommitArgsBuilder::exclude_path] and
+	/// [crate::CommitArgsBuilder::exclude_generated].
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     let args = CommitArgs::builder().exclude_path("vendor").build().unwrap();
+	///     if let Ok(loc) = repo.loc(args) {
+	///         for (extension, lines) in loc {
+	///             println!("{extension}: {lines} lines");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn loc(&self, args: CommitArgs) -> anyhow::Result<HashMap<String, usize>> {
+		args.validate()?;
+		// every line matches the empty pattern, `-c` prints the number of lines of each file
+		let command = self
+			.git()?
+			.with_debug(false)
+			.with_args(["grep", "-I", "-z", "-c", "", "HEAD", "--"])
+			.with_args(args.excluded_pathspecs());
+		let output = self.output(command)?;
+		// `git grep` exits with 1 when nothing matches (e.g. an empty tree)
+		if !output.status.success() && output.status.code() != Some(1) {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("failed to count the lines of code: {}", stderr.trim()));
+		}
+		parse_loc(&String::from_utf8_lossy(&output.stdout))
+	}
+
+	/// Returns the share of the lines of the files at HEAD (between 0 and 1) of each language, from the most used
+	/// one: a lightweight summary like the one of GitHub's linguist.
+	/// The language is guessed from the file extension with a small built-in table (`rs` is Rust, `py` Python...),
+	/// the unknown extensions are returned as they are, and the files without extension with an empty name.
+	/// The lines are counted like in [Repo::loc], skipping the generated files
+	/// (see [crate::CommitArgsBuilder::exclude_generated]).
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(languages) = repo.language_breakdown() {
+	///         for (language, share) in languages {
+	///             println!("{language}: {:.1}%", share * 100.0);
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn language_breakdown(&self) -> anyhow::Result<Vec<(String, f64)>> {
+		let loc = self.loc(CommitArgs::builder().exclude_generated(true).build()?)?;
+		let mut lines: HashMap<String, usize> = HashMap::new();
+		for (extension, count) in loc {
+			let language = language_name(&extension).map_or(extension, str::to_string);
+			*lines.entry(language).or_default() += count;
+		}
+
+		let total = lines.values().sum::<usize>();
+		let mut languages = lines
+			.into_iter()
+			.filter(|(_, count)| *count > 0)
+			.map(|(language, count)| (language, count as f64 / total as f64))
+			.collect::<Vec<_>>();
+		languages.sort_by(|(l1, s1), (l2, s2)| s2.total_cmp(s1).then_with(|| l1.cmp(l2)));
+		Ok(languages)
+	}
+
+	/// Returns the number of lines of the given file currently owned by each author, according to `git blame`.
+	/// The path is relative to the root of the repository, and the working tree version of the file is used:
+	/// the uncommitted lines are skipped, so only the committed lines are counted.
+	/// # Examples:
+	/// ```rust
+	/// use std::path::Path;
+	/// use gitstats::Repo;
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(owners) = repo.blame_summary(Path::new("src/lib.rs")) {
+	///         for (author, lines) in owners {
+	///             println!("{author}: {lines} lines");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn blame_summary(&self, path: &Path) -> anyhow::Result<HashMap<Author, usize>> {
+		let command = self
+			.git()?
+			.with_debug(false)
+			.with_args([OsStr::new("blame"), OsStr::new("--line-porcelain"), OsStr::new("--"), path.as_os_str()]);
+		let output = self.output(command)?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("failed to blame `{}`: {}", path.display(), stderr.trim()));
+		}
+		Ok(parse_blame(&String::from_utf8_lossy(&output.stdout)))
+	}
+
+	/// Returns the files that, among the commits matching the input arguments, were only ever touched by a
+	/// single author (knowledge silos), grouped by that author.
+	/// The files of each author are sorted by path.
+	/// Fails if the files of any commit are truncated by [crate::CommitArgsBuilder::max_stat_files], as the
+	/// missing files could hide other authors.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(silos) = repo.solo_owned_files(CommitArgs::default()) {
+	///         for (author, files) in silos {
+	///             println!("{author} is the only author of {} files", files.len());
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn solo_owned_files(&self, args: CommitArgs) -> anyhow::Result<HashMap<Author, Vec<String>>> {
+		let commits = self.list_commit_details(args)?;
+		let mut authors_per_file: HashMap<String, HashMap<(String, Option<String>), Author>> = HashMap::new();
+		for commit in commits {
+			if commit.truncated {
+				return Err(anyhow!("the files of commit {} are truncated by max_stat_files", commit.hash));
+			}
+			for file in commit.files {
+				let authors = authors_per_file.entry(file.path).or_default();
+				authors.entry(commit.author.identity_key()).or_insert_with(|| commit.author.clone());
+			}
+		}
+
+		let mut result: HashMap<Author, Vec<String>> = HashMap::new();
+		for (path, authors) in authors_per_file {
+			if authors.len() == 1 {
+				let author = authors.into_values().next().unwrap();
+				result.entry(author).or_default().push(path);
+			}
+		}
+		result.values_mut().for_each(|files| files.sort());
+		Ok(result)
+	}
+
+	/// Returns the distinct authors of the commits matching the input arguments, sorted by name and email
+	/// (case-insensitive).
+	/// Only the author names and emails are read, so this is much cheaper than computing the commit stats
+	/// when only the list of contributors is needed.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(contributors) = repo.contributors(CommitArgs::default()) {
+	///         for author in contributors {
+	///             println!("{author}");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn contributors(&self, args: CommitArgs) -> anyhow::Result<Vec<Author>> {
+		args.validate()?;
+		let command = self.git()?.with_args(["log", "--pretty=format:%at%x00%aN%x00%aE"]).with_args(args.clone());
+		let output = self.output(command)?;
+		let authors = String::from_utf8_lossy(&output.stdout)
+			.lines()
+			.filter_map(|line| parse_timestamped_author(line, &args))
+			.map(|author| (author.identity_key(), author))
+			.collect::<HashMap<_, _>>();
+		let mut authors = authors.into_values().collect::<Vec<_>>();
+		authors.sort_by(|a, b| {
+			cmp_ignore_case(&a.name, &b.name)
+				.then_with(|| cmp_ignore_case(a.email.as_deref().unwrap_or(""), b.email.as_deref().unwrap_or("")))
+				.then_with(|| a.identity_cmp(b))
+		});
+		Ok(authors)
+	}
+
+	/// Returns the number of commits of each author matching the input arguments, sorted from the author
+	/// with the most commits to the one with the fewest (ties are sorted by name).
+	/// Backed by `git shortlog`, it skips the per-commit stats entirely: use it when only the commits count is
+	/// needed, instead of [Repo::commits_stats] and [crate::traits::CommitStatsExt::commits_per_author].
+	/// With an author date range or [crate::CommitArgsBuilder::use_mailmap] the counts are read from `git log`
+	/// instead, as shortlog can't filter on them. The authors are always the mailmap'ed ones, as in the commit
+	/// stats.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(leaderboard) = repo.commit_counts_per_author(CommitArgs::default()) {
+	///         for (author, count) in leaderboard {
+	///             println!("{author}: {count} commits");
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn commit_counts_per_author(&self, args: CommitArgs) -> anyhow::Result<Vec<(Author, usize)>> {
+		args.validate()?;
+		if args.has_author_date_range() || args.use_mailmap {
+			// shortlog can't filter on the author date, and has no `--use-mailmap` to match the author filters
+			// against the mailmap'ed identities: count the commits of the log instead
+			let command = self.git()?.with_args(["log", "--pretty=format:%at%x00%aN%x00%aE"]).with_args(args.clone());
+			let output = self.output(command)?;
+			let stdout = String::from_utf8_lossy(&output.stdout);
+			let authors = stdout.lines().filter_map(|line| parse_timestamped_author(line, &args));
+			return Ok(sort_commit_counts(authors.map(|author| (author, 1))));
+		}
+
+		// every scope passes a revision (`--all`, `HEAD` or the branch): without one, shortlog would read the log
+		// from the standard input
+		let command = self
+			.git()?
+			.with_args(["shortlog", "--summary", "--numbered", "--email"])
+			.with_args(args)
+			.stdin(Some(Stdio::null()));
+		let output = self.output(command)?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("failed to count the commits per author: {}", stderr.trim()));
+		}
+
+		let mut counts: Vec<(Author, usize)> = Vec::new();
+		for line in String::from_utf8_lossy(&output.stdout).lines() {
+			let Some((count, author)) = line.trim().split_once('\t') else {
+				continue;
+			};
+			let count = count.trim().parse::<usize>().with_context(|| format!("invalid shortlog line `{line}`"))?;
+			counts.push((Author::try_from(author.trim())?, count));
+		}
+
+		Ok(sort_commit_counts(counts))
+	}
+
+	/// Returns, for each author of the commits matching the input arguments, the number of commits, the dates of
+	/// the first and the last one and the sum of their stats: the counts of `git shortlog` along with the stats,
+	/// read with a single `git log`.
+	/// The authors are merged like in [crate::traits::CommitStatsExt::commits_per_author] and sorted by number
+	/// of commits, from the highest to the lowest.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// fn main() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(summary) = repo.author_summary(CommitArgs::default()) {
+	///         for item in summary {
+	///             println!("{}: {} commits, {}", item.author, item.commits_count, item.stats);
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub fn author_summary(&self, args: CommitArgs) -> anyhow::Result<Vec<AuthorSummary>> {
+		let commits = self.list_commit_details(args)?;
+		let summary = commits
+			.commits_per_author()
+			.global_stats(SortStatsBy::Commits)
+			.into_iter()
+			.map(|stat| AuthorSummary {
+				author: stat.author,
+				commits_count: stat.commits_count,
+				first_commit: stat.first_commit,
+				last_commit: stat.last_commit,
+				stats: stat.stats,
+			})
+			.collect();
+		Ok(summary)
+	}
+
+	/// Returns the full ref names of the tags, sorted from the oldest to the newest
+	fn release_tags(&self) -> anyhow::Result<Vec<String>> {
+		let command = self.git()?.with_args([
+			"for-each-ref",
+			"--sort=creatordate",
+			"--format=%(refname)",
+			"refs/tags",
+		]);
+		let output = self.output(command)?;
+		output
+			.stdout
+			.lines()
+			.filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+			.map(|line| line.map_err(anyhow::Error::from))
+			.collect()
+	}
+
+	/// Returns the git command, using the executable set with [Repo::with_git_binary] or the one found in the `PATH`
+	fn git(&self) -> anyhow::Result<CommandBuilder> {
+		let git = match &self.git_binary {
+			// checked here, as a command which cannot be spawned makes simple_cmd panic
+			Some(path) if !path.is_file() => return Err(anyhow!("git executable not found: {}", path.display())),
+			Some(path) => path.clone(),
+			None => which("git")?,
+		};
+		//Ok(CommandBuilder::new(git).current_dir(&self.inner).with_debug(true))
 		Ok(CommandBuilder::new(git).with_debug(true).with_arg("-C").with_arg(&self.inner))
 	}
+
+	/// Run the command and wait for its output, enforcing the repository timeout (if any).
+	///
+	/// The git process is polled until it exits or the timeout expires: [Error::Timeout] is only returned when
+	/// the watchdog actually killed it, a git failing on its own is reported with its exit status.
+	fn output(&self, command: CommandBuilder) -> anyhow::Result<Output> {
+		let Some(timeout) = self.timeout else {
+			return Ok(command.build().output()?);
+		};
+
+		let mut command: Command = command.stdout(Some(Stdio::piped())).stderr(Some(Stdio::piped())).into();
+		let mut child = command.spawn().context("failed to spawn git")?;
+		let stdout = read_pipe(child.stdout.take());
+		let stderr = read_pipe(child.stderr.take());
+
+		let deadline = Instant::now() + timeout;
+		let mut killed = false;
+		let status = loop {
+			if let Some(status) = child.try_wait()? {
+				break status;
+			}
+			let now = Instant::now();
+			if now >= deadline {
+				killed = child.kill().is_ok();
+				break child.wait()?;
+			}
+			thread::sleep((deadline - now).min(WATCHDOG_POLL));
+		};
+
+		if killed {
+			// the readers are left behind: a process spawned by git may still hold the pipes open
+			return Err(Error::Timeout(timeout).into());
+		}
+		Ok(Output {
+			status,
+			stdout: stdout.join().map_err(|_| anyhow!("failed to read the git stdout"))??,
+			stderr: stderr.join().map_err(|_| anyhow!("failed to read the git stderr"))??,
+		})
+	}
+}
+
+/// Interval between two checks of a git process running with a timeout
+const WATCHDOG_POLL: Duration = Duration::from_millis(10);
+
+/// Read the whole pipe in a separate thread, so that git never blocks writing to a full pipe
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<std::io::Result<Vec<u8>>> {
+	thread::spawn(move || {
+		let mut buffer = Vec::new();
+		if let Some(mut pipe) = pipe {
+			pipe.read_to_end(&mut buffer)?;
+		}
+		Ok(buffer)
+	})
+}
+
+/// Maximum number of commits passed to a single `git show` by [Repo::commit_stats_many]
+const SHOW_CHUNK: usize = 256;
+
+/// Async versions of the git operations, running git with [tokio::process::Command] instead of blocking the
+/// current thread. They require a tokio runtime and share the parsing logic with the sync versions.
+#[cfg(feature = "async")]
+impl Repo {
+	/// Same as [Repo::fetch], without blocking the executor.
+	/// Unlike [Repo::fetch], a failure of git is returned as an error, with the git error message.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::Repo;
+	///
+	/// async fn fetch() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Err(err) = repo.fetch_async().await {
+	///         println!("Error: {err}");
+	///     }
+	/// }
+	/// ```
+	pub async fn fetch_async(&self) -> anyhow::Result<()> {
+		let output = self.output_async(self.git()?.arg("fetch")).await?;
+		if !output.status.success() {
+			let stderr = String::from_utf8_lossy(&output.stderr);
+			return Err(anyhow!("Failed to fetch remote: {}", stderr.trim()));
+		}
+		Ok(())
+	}
+
+	/// Same as [Repo::list_commits], without blocking the executor
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// async fn list() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     match repo.list_commits_async(CommitArgs::default()).await {
+	///         Ok(commits) => println!("got {} commits", commits.len()),
+	///         Err(err) => println!("Error: {err}"),
+	///     }
+	/// }
+	/// ```
+	pub async fn list_commits_async(&self, options: CommitArgs) -> anyhow::Result<Vec<CommitHash>> {
+		let command = self.list_commits_command(&options)?;
+		let output = self.output_async(command).await?;
+		Ok(parse_commit_list(&output.stdout, &options))
+	}
+
+	/// Same as [Repo::commits_stats], without blocking the executor.
+	/// Instead of a git process per commit, the commits are read in batches with a single `git show` each.
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::{CommitArgs, Repo};
+	///
+	/// async fn stats() {
+	///     let repo = Repo::new("/custom/path/to/repo");
+	///     if let Ok(commits) = repo.list_commits_async(CommitArgs::default()).await {
+	///         let stats = repo.commits_stats_async(&commits).await;
+	///     }
+	/// }
+	/// ```
+	pub async fn commits_stats_async(&self, commits: &[CommitHash]) -> anyhow::Result<Vec<CommitDetail>> {
+		let mut result = Vec::with_capacity(commits.len());
+		for chunk in commits.chunks(SHOW_CHUNK) {
+			let command = self.show_details_command(chunk.iter().map(|commit| commit.0.as_str()))?;
+			result.extend(parse_shown_commits(chunk, self.output_async(command).await?, self.max_stat_files)?);
+		}
+		Ok(result)
+	}
+
+	/// Same as [Repo::output], awaiting the git process. The process is killed if the future is dropped
+	async fn output_async(&self, command: CommandBuilder) -> anyhow::Result<Output> {
+		let command: Command = command.into();
+		let mut command = tokio::process::Command::from(command);
+		command.kill_on_drop(true);
+		let output = command.output();
+		let output = match self.timeout {
+			Some(timeout) => tokio::time::timeout(timeout, output).await.map_err(|_| Error::Timeout(timeout))?,
+			None => output.await,
+		};
+		output.context("failed to run git")
+	}
+}
+
+/// Iterator over the stdout lines of a running git process.
+/// Once the output is over, a last `Err` item with the stderr of git is returned if git failed.
+/// The child process is killed and reaped when the iterator is dropped.
+struct ChildLines {
+	child: Child,
+	lines: Lines<BufReader<ChildStdout>>,
+	/// stderr is read in a separate thread, so that git cannot block on a full pipe
+	stderr: Option<JoinHandle<String>>,
+	finished: bool,
+}
+
+impl ChildLines {
+	fn spawn(command: CommandBuilder) -> anyhow::Result<Self> {
+		let mut command: Command = command.stderr(Some(Stdio::piped())).into();
+		let mut child = command.spawn().context("failed to spawn git")?;
+		let stdout = child.stdout.take().ok_or(anyhow!("failed to capture git output"))?;
+		let mut stderr = child.stderr.take().ok_or(anyhow!("failed to capture git errors"))?;
+		let stderr = thread::spawn(move || {
+			let mut output = String::new();
+			let _ = stderr.read_to_string(&mut output);
+			output
+		});
+		Ok(ChildLines {
+			child,
+			lines: BufReader::new(stdout).lines(),
+			stderr: Some(stderr),
+			finished: false,
+		})
+	}
+
+	/// Wait for git to exit, returning an error if it failed
+	fn exit_error(&mut self) -> Option<anyhow::Error> {
+		let status = match self.child.wait() {
+			Ok(status) => status,
+			Err(err) => return Some(err.into()),
+		};
+		if status.success() {
+			return None;
+		}
+		let stderr = self.stderr.take().and_then(|stderr| stderr.join().ok()).unwrap_or_default();
+		Some(anyhow!("git failed ({status}): {}", stderr.trim()))
+	}
+}
+
+impl Iterator for ChildLines {
+	type Item = anyhow::Result<String>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.finished {
+			return None;
+		}
+		match self.lines.next() {
+			Some(line) => Some(line.map_err(anyhow::Error::from)),
+			None => {
+				self.finished = true;
+				self.exit_error().map(Err)
+			}
+		}
+	}
+}
+
+impl Drop for ChildLines {
+	fn drop(&mut self) {
+		let _ = self.child.kill();
+		let _ = self.child.wait();
+	}
+}
+
+/// Parse the output of [Repo::show_details_command] for the given commits, returning their details in the same
+/// order. As in [Repo::commit_stats], the hash of each commit is the one requested (which may be abbreviated)
+fn parse_shown_commits(
+	hashes: &[CommitHash],
+	output: Output,
+	max_stat_files: Option<u32>,
+) -> anyhow::Result<Vec<CommitDetail>> {
+	if !output.status.success() {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(anyhow!("failed to read the commits stats: {}", stderr.trim()));
+	}
+	let details = parse_commit_details(&String::from_utf8_lossy(&output.stdout), max_stat_files)?;
+	hashes
+		.iter()
+		.map(|hash| {
+			let detail = details
+				.iter()
+				.find(|detail| detail.hash.0.starts_with(&hash.0.to_lowercase()))
+				.ok_or_else(|| Error::UnknownCommit(hash.to_string()))?;
+			Ok(CommitDetail {
+				hash: hash.clone(),
+				..detail.clone()
+			})
+		})
+		.collect()
+}
+
+/// Parse the output of [Repo::list_commits_command]
+fn parse_commit_list(stdout: &[u8], options: &CommitArgs) -> Vec<CommitHash> {
+	stdout
+		.lines()
+		.filter_map(|line| if let Ok(line) = line { hash_in_author_date_range(&line, options) } else { None })
+		.collect::<Vec<_>>()
+}
+
+/// Parse a `<hash> <author timestamp>` line, `None` if the commit is outside the author date range of the arguments
+fn hash_in_author_date_range(line: &str, options: &CommitArgs) -> Option<CommitHash> {
+	let (hash, timestamp) = line.split_once(' ')?;
+	let timestamp = timestamp.parse::<i64>().ok()?;
+	options.matches_author_date(timestamp).then(|| CommitHash(hash.to_string()))
+}
+
+/// Parse a `%at%x00%aN%x00%aE` line, `None` if the commit is outside the author date range of the arguments
+fn parse_timestamped_author(line: &str, options: &CommitArgs) -> Option<Author> {
+	let mut parts = line.splitn(3, '\0');
+	let timestamp = parts.next()?.parse::<i64>().ok()?;
+	let (name, email) = (parts.next()?, parts.next()?);
+	options.matches_author_date(timestamp).then(|| Author::new(name).with_email(email))
+}
+
+/// Sum the commit counts of the same author (case-insensitive) and sort them from the highest to the lowest,
+/// ties by name
+fn sort_commit_counts(counts: impl IntoIterator<Item = (Author, usize)>) -> Vec<(Author, usize)> {
+	let mut totals: HashMap<(String, Option<String>), (Author, usize)> = HashMap::new();
+	for (author, count) in counts {
+		totals.entry(author.identity_key()).or_insert_with(|| (author, 0)).1 += count;
+	}
+	let mut counts = totals.into_values().collect::<Vec<_>>();
+	counts.sort_by(|(a1, c1), (a2, c2)| {
+		c2.cmp(c1).then_with(|| cmp_ignore_case(&a1.name, &a2.name)).then_with(|| a1.identity_cmp(a2))
+	});
+	counts
+}
+
+/// Parse the output of `git show`/`git log` for a list of commits, each one formatted with [COMMIT_FORMAT]
+/// prefixed by `0x1e`. With [MergeDiffMode::Separate] a merge commit is repeated once per parent: the
+/// consecutive records of the same commit are merged, summing their stats.
+pub(crate) fn parse_commit_details(output: &str, max_stat_files: Option<u32>) -> anyhow::Result<Vec<CommitDetail>> {
+	let mut commits: Vec<CommitDetail> = Vec::new();
+	for record in output.split('\x1e').filter(|record| !record.trim().is_empty()) {
+		let commit = parse_commit_detail(record, max_stat_files)?;
+		match commits.last_mut() {
+			Some(last) if last.hash.0 == commit.hash.0 => merge_commit_record(last, commit, max_stat_files),
+			_ => commits.push(commit),
+		}
+	}
+	Ok(commits)
+}
+
+/// Merge a record of the same commit (the diff against another parent of a merge) into `commit`
+fn merge_commit_record(commit: &mut CommitDetail, other: CommitDetail, max_stat_files: Option<u32>) {
+	commit.stats += other.stats;
+	commit.files.extend(other.files);
+	commit.renames.extend(other.renames);
+	commit.truncated |= other.truncated;
+	if let Some(max) = max_stat_files.filter(|max| commit.files.len() > *max as usize) {
+		commit.files.truncate(max as usize);
+		commit.truncated = true;
+	}
+}
+
+/// Streaming version of [parse_commit_details]: parses the commits as the lines are read from a running
+/// `git log`, keeping at most one commit in memory
+struct CommitDetails {
+	lines: ChildLines,
+	max_stat_files: Option<u32>,
+	/// the lines of the record being read
+	record: Option<String>,
+	/// the last parsed commit, returned once the next commit is known to be a different one
+	pending: Option<CommitDetail>,
+}
+
+impl CommitDetails {
+	fn new(lines: ChildLines, max_stat_files: Option<u32>) -> Self {
+		CommitDetails {
+			lines,
+			max_stat_files,
+			record: None,
+			pending: None,
+		}
+	}
+
+	/// Parse a complete record, returning the previous commit if the record belongs to a new one
+	fn complete(&mut self, record: &str) -> anyhow::Result<Option<CommitDetail>> {
+		let commit = parse_commit_detail(record, self.max_stat_files)?;
+		match self.pending.as_mut() {
+			Some(pending) if pending.hash.0 == commit.hash.0 => {
+				merge_commit_record(pending, commit, self.max_stat_files);
+				Ok(None)
+			}
+			_ => Ok(self.pending.replace(commit)),
+		}
+	}
+}
+
+impl Iterator for CommitDetails {
+	type Item = anyhow::Result<CommitDetail>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			match self.lines.next() {
+				Some(Ok(line)) => {
+					if let Some(header) = line.strip_prefix('\x1e') {
+						if let Some(record) = self.record.replace(header.to_string()) {
+							match self.complete(&record) {
+								Ok(Some(commit)) => return Some(Ok(commit)),
+								Ok(None) => {}
+								Err(err) => return Some(Err(err)),
+							}
+						}
+					} else if let Some(record) = self.record.as_mut() {
+						record.push('\n');
+						record.push_str(&line);
+					}
+				}
+				Some(Err(err)) => return Some(Err(err)),
+				None => {
+					if let Some(record) = self.record.take() {
+						match self.complete(&record) {
+							Ok(Some(commit)) => return Some(Ok(commit)),
+							Ok(None) => {}
+							Err(err) => return Some(Err(err)),
+						}
+					}
+					return self.pending.take().map(Ok);
+				}
+			}
+		}
+	}
+}
+
+/// Parse the output of `git show`/`git log` for a single commit, formatted with [COMMIT_FORMAT]
+/// and followed by the `--numstat` and `--shortstat` lines.
+/// At most `max_stat_files` files are collected, if the limit is hit the commit is marked as truncated.
+fn parse_commit_detail(output: &str, max_stat_files: Option<u32>) -> anyhow::Result<CommitDetail> {
+	let mut commit_hash: Option<String> = None;
+	let mut author_name: Option<String> = None;
+	let mut author_email: Option<String> = None;
+	let mut author_date: Option<i64> = None;
+	let mut committer_date: Option<i64> = None;
+	let mut co_authors: Vec<Author> = Vec::new();
+	let mut stats: Option<CommitStats> = None;
+	let mut files: Vec<FileStat> = Vec::new();
+	let mut renames: Vec<(String, String)> = Vec::new();
+	let mut files_count: u32 = 0;
+	let mut truncated = false;
+
+	for (index, line) in output.lines().enumerate() {
+		match index {
+			0 => commit_hash = Some(line.to_string()),
+			1 => author_name = Some(line.to_string()),
+			2 => author_email = Some(line.to_string()),
+			3 => author_date = Some(parse_timestamp(line, "author")?),
+			4 => committer_date = Some(parse_timestamp(line, "committer")?),
+			5 => {
+				co_authors = line
+					.split('\x1f')
+					.filter(|value| !value.trim().is_empty())
+					.filter_map(|value| Author::try_from(value.trim()).ok())
+					.collect();
+			}
+			_ => {
+				if let Some(find) = NUMSTATS_RE.captures(line) {
+					files_count += 1;
+					let mut path = find.name("filename").unwrap().as_str().to_string();
+					if let Some((from, to)) = parse_rename(&path) {
+						path.clone_from(&to);
+						renames.push((from, to));
+					}
+					if max_stat_files.is_some_and(|max| files.len() >= max as usize) {
+						truncated = true;
+						continue;
+					}
+					files.push(FileStat {
+						path,
+						lines_added: find.name("additions").map_or(0, |f| f.as_str().parse::<u64>().unwrap_or(0)),
+						lines_deleted: find.name("deletions").map_or(0, |f| f.as_str().parse::<u64>().unwrap_or(0)),
+					});
+				} else if let Some(short_stats) = parse_short_stats(line) {
+					stats = Some(short_stats);
+				}
+			}
+		}
+	}
+
+	let missing = |field: &str| Error::Parse(format!("{field} not found"));
+	let commit_hash = commit_hash.filter(|hash| !hash.trim().is_empty()).ok_or_else(|| missing("commit hash"))?;
+	let author_name = author_name.ok_or_else(|| missing("author name"))?;
+	let author_email = author_email.ok_or_else(|| missing("author email"))?;
+	let author_date = author_date.ok_or_else(|| missing("author timestamp"))?;
+	let committer_date = committer_date.ok_or_else(|| missing("committer timestamp"))?;
+
+	// without the shortstat line (e.g. merge commits), fall back to the numstat lines
+	let stats = stats.unwrap_or_else(|| {
+		let mut stats = files.iter().map(CommitStats::from).fold(CommitStats::default(), |acc, item| acc + item);
+		stats.files_changed = files_count.into();
+		stats
+	});
+
+	Ok(CommitDetail {
+		hash: CommitHash(commit_hash),
+		author: Author::new(author_name).with_email(&author_email),
+		author_timestamp: author_date,
+		committer_timestamp: committer_date,
+		stats,
+		co_authors,
+		files,
+		renames,
+		truncated,
+	})
+}
+
+/// Parse a renamed path of the `--numstat` output, either `old => new` or with the common parts outside of
+/// the braces, like `src/{old => new}/lib.rs` or `src/{ => nested}/lib.rs`. Returns the old and the new path
+fn parse_rename(path: &str) -> Option<(String, String)> {
+	let braces = path.find('{').and_then(|start| Some((start, start + path[start..].find('}')?)));
+	match braces {
+		Some((start, end)) if path[start..end].contains(" => ") => {
+			let (prefix, suffix) = (&path[..start], &path[end + 1..]);
+			let (from, to) = path[start + 1..end].split_once(" => ")?;
+			// an empty side is a parent directory, e.g. `{ => nested}/lib.rs` moves `lib.rs` to `nested/lib.rs`
+			let join = |middle: &str| match middle {
+				"" => format!("{prefix}{}", suffix.strip_prefix('/').unwrap_or(suffix)),
+				_ => format!("{prefix}{middle}{suffix}"),
+			};
+			Some((join(from), join(to)))
+		}
+		_ => path.split_once(" => ").map(|(from, to)| (from.to_string(), to.to_string())),
+	}
+}
+
+/// Parse the unix timestamp of the `kind` (author or committer) date of a commit
+fn parse_timestamp(line: &str, kind: &str) -> Result<i64, Error> {
+	line.trim().parse::<i64>().map_err(|_| Error::Parse(format!("invalid {kind} timestamp: `{line}`")))
+}
+
+/// Sum the lines per extension in the output of `git grep -z -c '' HEAD`: each line is `HEAD:<path>\0<count>`
+fn parse_loc(output: &str) -> anyhow::Result<HashMap<String, usize>> {
+	let mut loc: HashMap<String, usize> = HashMap::new();
+	for line in output.lines() {
+		let Some((path, count)) = line.split_once('\0') else {
+			continue;
+		};
+		let path = path.strip_prefix("HEAD:").unwrap_or(path);
+		let count = count.parse::<usize>().with_context(|| format!("invalid line count for `{path}`"))?;
+		let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+		*loc.entry(extension.to_string()).or_default() += count;
+	}
+	Ok(loc)
+}
+
+/// The language of the files with the given extension, `None` if unknown
+fn language_name(extension: &str) -> Option<&'static str> {
+	let language = match extension.to_ascii_lowercase().as_str() {
+		"rs" => "Rust",
+		"py" | "pyi" => "Python",
+		"js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+		"ts" | "tsx" | "mts" | "cts" => "TypeScript",
+		"java" => "Java",
+		"kt" | "kts" => "Kotlin",
+		"scala" => "Scala",
+		"swift" => "Swift",
+		"m" | "mm" => "Objective-C",
+		"c" | "h" => "C",
+		"cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => "C++",
+		"cs" => "C#",
+		"go" => "Go",
+		"rb" => "Ruby",
+		"php" => "PHP",
+		"dart" => "Dart",
+		"lua" => "Lua",
+		"ex" | "exs" => "Elixir",
+		"erl" | "hrl" => "Erlang",
+		"hs" => "Haskell",
+		"clj" | "cljs" => "Clojure",
+		"r" => "R",
+		"pl" | "pm" => "Perl",
+		"sh" | "bash" | "zsh" => "Shell",
+		"ps1" => "PowerShell",
+		"sql" => "SQL",
+		"html" | "htm" => "HTML",
+		"css" => "CSS",
+		"scss" | "sass" => "Sass",
+		"vue" => "Vue",
+		"svelte" => "Svelte",
+		"md" | "markdown" => "Markdown",
+		"json" => "JSON",
+		"yml" | "yaml" => "YAML",
+		"toml" => "TOML",
+		"xml" => "XML",
+		"gradle" => "Gradle",
+		"cmake" => "CMake",
+		"proto" => "Protocol Buffers",
+		_ => return None,
+	};
+	Some(language)
+}
+
+/// Count the lines per author in the output of `git blame --line-porcelain`, skipping the uncommitted lines
+fn parse_blame(output: &str) -> HashMap<Author, usize> {
+	let mut result: HashMap<Author, usize> = HashMap::new();
+	let mut committed = false;
+	let mut name = "";
+	let mut email = "";
+	for line in output.lines() {
+		if line.starts_with('\t') {
+			// the content of the line closes its header
+			if committed {
+				let email = email.trim_start_matches('<').trim_end_matches('>');
+				let author = Author::new(name).with_email_opt(Some(email).filter(|email| !email.is_empty()));
+				*result.entry(author).or_default() += 1;
+			}
+		} else if let Some(value) = line.strip_prefix("author ") {
+			name = value;
+		} else if let Some(value) = line.strip_prefix("author-mail ") {
+			email = value;
+		} else if let Some((hash, _)) = line.split_once(' ') {
+			if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+				committed = hash.chars().any(|c| c != '0');
+			}
+		}
+	}
+	result
+}
+
+/// Parse the output of `git status --porcelain`: the first two characters of each line are the status of the
+/// file in the index and in the working tree
+fn parse_status(output: &str) -> WorkTreeStatus {
+	let mut status = WorkTreeStatus::default();
+	for line in output.lines() {
+		let mut chars = line.chars();
+		let (Some(index), Some(worktree)) = (chars.next(), chars.next()) else {
+			continue;
+		};
+		match (index, worktree) {
+			('?', '?') => status.untracked += 1,
+			('!', '!') => {}
+			('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => status.conflicted += 1,
+			_ => {
+				if index != ' ' {
+					status.staged += 1;
+				}
+				if worktree != ' ' {
+					status.modified += 1;
+				}
+			}
+		}
+	}
+	status
+}
+
+/// Parse the output of `git remote -v`, merging the fetch and push lines of each remote
+fn parse_remotes(output: &str) -> Vec<Remote> {
+	let mut remotes: Vec<Remote> = Vec::new();
+	for line in output.lines() {
+		let Some((name, rest)) = line.split_once('\t') else {
+			continue;
+		};
+		let Some((url, kind)) = rest.rsplit_once(' ') else {
+			continue;
+		};
+		let index = match remotes.iter().position(|remote| remote.name == name) {
+			Some(index) => index,
+			None => {
+				remotes.push(Remote {
+					name: name.to_string(),
+					fetch_url: String::new(),
+					push_url: None,
+				});
+				remotes.len() - 1
+			}
+		};
+		let remote = &mut remotes[index];
+		match kind {
+			"(fetch)" => remote.fetch_url = url.to_string(),
+			"(push)" => remote.push_url = Some(url.to_string()),
+			_ => {}
+		}
+	}
+	for remote in remotes.iter_mut() {
+		if remote.push_url.as_ref() == Some(&remote.fetch_url) {
+			remote.push_url = None;
+		}
+	}
+	remotes
+}
+
+/// Parse a `--shortstat` line (e.g. ` 2 files changed, 3 insertions(+), 1 deletion(-)`)
+fn parse_short_stats(line: &str) -> Option<CommitStats> {
+	SHORT_STATS_RE.captures(line).map(|find| CommitStats {
+		files_changed: find.name("files").map_or(0, |f| f.as_str().parse::<u64>().unwrap_or(0)),
+		lines_added: find.name("insertions").map_or(0, |f| f.as_str().parse::<u64>().unwrap_or(0)),
+		lines_deleted: find.name("deletions").map_or(0, |f| f.as_str().parse::<u64>().unwrap_or(0)),
+	})
+}
+
+/// Count the files and lines changed in a combined diff (`git show --cc`).
+/// A line counts as added (or deleted) if it is added (or deleted) with respect to any of the parents.
+fn parse_combined_diff(output: &str) -> CommitStats {
+	let mut stats = CommitStats::default();
+	// number of parents of the current hunk, `None` while outside of a hunk
+	let mut parents: Option<usize> = None;
+
+	for line in output.lines() {
+		if line.starts_with("diff ") {
+			stats.files_changed += 1;
+			parents = None;
+		} else if line.starts_with("@@") {
+			parents = Some(line.chars().take_while(|c| *c == '@').count() - 1);
+		} else if let Some(parents) = parents {
+			let prefix = line.get(..parents).unwrap_or(line);
+			if prefix.contains('+') {
+				stats.lines_added += 1;
+			} else if prefix.contains('-') {
+				stats.lines_deleted += 1;
+			}
+		}
+	}
+	stats
+}
+
+impl MergeDiffMode {
+	fn as_arg(&self) -> &'static str {
+		match self {
+			MergeDiffMode::Off => "--diff-merges=off",
+			MergeDiffMode::FirstParent => "--diff-merges=first-parent",
+			MergeDiffMode::Separate => "--diff-merges=separate",
+		}
+	}
 }
 
+/// Same as [Repo::new]: the path is not validated, use [Repo::open] for that
 impl<'a, T: ?Sized + AsRef<OsStr>> From<&'a T> for Repo {
 	fn from(s: &'a T) -> Self {
 		Repo::new(s)
@@ -0,0 +1,190 @@
+//! Serialization helpers for the `HashMap` based types.
+//! The keys are serialized as strings, in ascending order, so that the output is deterministic and can be
+//! deserialized back. Authors are serialized as `Name <email>`, like their `Display`, so that an [Author] keyed
+//! map is a plain JSON object.
+//!
+//! The stats types already use them, but they can also be used for custom maps:
+//! ```rust
+//! use std::collections::HashMap;
+//! use gitstats::Author;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Report {
+//!     #[serde(with = "gitstats::ser::ordered_map")]
+//!     reviews: HashMap<Author, usize>,
+//! }
+//!
+//! fn main() {
+//!     let reviews = HashMap::from([(Author::new("Jane Doe").with_email("jane@example.com"), 3)]);
+//!     let json = serde_json::to_string(&Report { reviews }).unwrap();
+//!     assert_eq!(r#"{"reviews":{"Jane Doe <jane@example.com>":3}}"#, json);
+//! }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::impls::cmp_ignore_case;
+use crate::Author;
+
+/// A map key, with its ordering and string representation in the serialized output
+pub trait MapKey: Sized + Eq + Hash {
+	fn key_cmp(&self, other: &Self) -> Ordering;
+
+	fn to_key(&self) -> String;
+
+	fn from_key(key: &str) -> Option<Self>;
+}
+
+impl MapKey for String {
+	fn key_cmp(&self, other: &Self) -> Ordering {
+		self.cmp(other)
+	}
+
+	fn to_key(&self) -> String {
+		self.clone()
+	}
+
+	fn from_key(key: &str) -> Option<Self> {
+		Some(key.to_string())
+	}
+}
+
+impl MapKey for u8 {
+	fn key_cmp(&self, other: &Self) -> Ordering {
+		self.cmp(other)
+	}
+
+	fn to_key(&self) -> String {
+		self.to_string()
+	}
+
+	fn from_key(key: &str) -> Option<Self> {
+		key.parse().ok()
+	}
+}
+
+impl MapKey for u32 {
+	fn key_cmp(&self, other: &Self) -> Ordering {
+		self.cmp(other)
+	}
+
+	fn to_key(&self) -> String {
+		self.to_string()
+	}
+
+	fn from_key(key: &str) -> Option<Self> {
+		key.parse().ok()
+	}
+}
+
+/// Authors are sorted by name, then by email (case-insensitive)
+impl MapKey for Author {
+	fn key_cmp(&self, other: &Self) -> Ordering {
+		cmp_ignore_case(&self.name, &other.name)
+			.then_with(|| cmp_ignore_case(self.email.as_deref().unwrap_or(""), other.email.as_deref().unwrap_or("")))
+			.then_with(|| self.identity_cmp(other))
+	}
+
+	fn to_key(&self) -> String {
+		self.to_string()
+	}
+
+	fn from_key(key: &str) -> Option<Self> {
+		Author::try_from(key).ok()
+	}
+}
+
+/// Returns the entries of the map sorted by key, in the same order of the serialized output
+pub(crate) fn sorted_entries<K: MapKey, V>(map: &HashMap<K, V>) -> Vec<(&K, &V)> {
+	let mut entries = map.iter().collect::<Vec<_>>();
+	entries.sort_by(|(k1, _), (k2, _)| k1.key_cmp(k2));
+	entries
+}
+
+/// A map serialized with its keys in ascending order
+struct Ordered<'a, K, V>(&'a HashMap<K, V>);
+
+impl<K: MapKey, V: Serialize> Serialize for Ordered<'_, K, V> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let entries = sorted_entries(self.0);
+		let mut map = serializer.serialize_map(Some(entries.len()))?;
+		for (key, value) in entries {
+			map.serialize_entry(&key.to_key(), value)?;
+		}
+		map.end()
+	}
+}
+
+fn parse_keys<K: MapKey, V, E: serde::de::Error>(map: HashMap<String, V>) -> Result<HashMap<K, V>, E> {
+	map.into_iter()
+		.map(|(key, value)| {
+			K::from_key(&key)
+				.map(|key| (key, value))
+				.ok_or_else(|| E::custom(format!("invalid map key `{key}`")))
+		})
+		.collect()
+}
+
+/// To be used with `#[serde(with = "gitstats::ser::ordered_map")]` on a `HashMap` with [MapKey] keys
+pub mod ordered_map {
+	use super::*;
+
+	pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		K: MapKey,
+		V: Serialize,
+		S: Serializer,
+	{
+		Ordered(map).serialize(serializer)
+	}
+
+	pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+	where
+		K: MapKey,
+		V: Deserialize<'de>,
+		D: Deserializer<'de>,
+	{
+		parse_keys(HashMap::<String, V>::deserialize(deserializer)?)
+	}
+}
+
+/// Same as [ordered_map], for maps of maps: the keys of both the outer and the inner maps are sorted
+pub mod ordered_nested_map {
+	use super::*;
+
+	pub fn serialize<K1, K2, V, S>(map: &HashMap<K1, HashMap<K2, V>>, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		K1: MapKey,
+		K2: MapKey,
+		V: Serialize,
+		S: Serializer,
+	{
+		let entries = sorted_entries(map);
+		let mut state = serializer.serialize_map(Some(entries.len()))?;
+		for (key, value) in entries {
+			state.serialize_entry(&key.to_key(), &Ordered(value))?;
+		}
+		state.end()
+	}
+
+	pub fn deserialize<'de, K1, K2, V, D>(deserializer: D) -> Result<HashMap<K1, HashMap<K2, V>>, D::Error>
+	where
+		K1: MapKey,
+		K2: MapKey,
+		V: Deserialize<'de>,
+		D: Deserializer<'de>,
+	{
+		let map = HashMap::<String, HashMap<String, V>>::deserialize(deserializer)?;
+		let map = map
+			.into_iter()
+			.map(|(key, value)| Ok((key, parse_keys(value)?)))
+			.collect::<Result<HashMap<_, _>, D::Error>>()?;
+		parse_keys(map)
+	}
+}
@@ -1,10 +1,14 @@
-#[cfg(test)]
+#[cfg(all(test, feature = "repo"))]
+#[allow(clippy::module_inception)]
 mod test {
 	use std::env::current_dir;
+	use std::ffi::OsStr;
 	use std::ops::Deref;
+	use std::path::Path;
+	use std::process::Command;
 	use std::time::{Duration, Instant};
 
-	use chrono::{DateTime, Months, Utc, Weekday};
+	use chrono::{DateTime, Months, NaiveDate, Utc, Weekday};
 	use comfy_table::Table;
 	use humansize::{BaseUnit, FormatSizeOptions};
 	use itertools::Itertools;
@@ -12,8 +16,12 @@ mod test {
 	use num_traits::cast::FromPrimitive;
 	use textplots::{AxisBuilder, LabelBuilder, LabelFormat, LineStyle, Plot, Shape, TickDisplay, TickDisplayBuilder};
 
+	use crate::repo::parse_commit_details;
 	use crate::traits::CommitStatsExt;
-	use crate::{Author, CommitArgs, CommitDetail, CommitHash, Repo, SortStatsBy};
+	use crate::{
+		Author, CommitArgs, CommitDetail, CommitHash, CommitStats, Error, HistoryScope, MergeDiffMode, Remote, Repo,
+		SortStatsBy, TagInterval, WorkTreeStatus,
+	};
 
 	lazy_static! {
 		static ref SINCE: DateTime<Utc> = Utc::now().checked_sub_months(Months::new(6)).unwrap();
@@ -37,7 +45,7 @@ mod test {
 			.with_thread_ids(false)
 			.with_thread_names(false)
 			.finish();
-		tracing::subscriber::set_global_default(subscriber).unwrap();
+		let _ = tracing::subscriber::set_global_default(subscriber);
 	}
 
 	fn checkout_repo() -> Repo {
@@ -53,7 +61,7 @@ mod test {
 		let current_dir = current_dir().unwrap();
 		let path = current_dir;
 		println!("path: {:?}", path);
-		let repo = Repo::try_from(&path).unwrap();
+		let repo = Repo::open(&path).unwrap();
 		println!("repo: {}", repo);
 
 		assert_eq!(path.to_str(), repo.to_str());
@@ -125,7 +133,7 @@ mod test {
 		let commits = repo.list_commits(args).unwrap();
 		println!("listed commits in {:?}", ticker.tick().0);
 		println!("total commits: {}", commits.len());
-		assert!(commits.len() > 0);
+		assert!(!commits.is_empty());
 
 		ticker.tick();
 		let stats = repo.commits_stats(&commits).unwrap();
@@ -140,7 +148,7 @@ mod test {
 		let repo = checkout_repo();
 		let commits = repo.list_commits(COMMIT_ARGS.clone()).unwrap();
 		println!("total commits: {}", commits.len());
-		assert!(commits.len() > 0);
+		assert!(!commits.is_empty());
 
 		let stats: Vec<CommitDetail> = repo.commits_stats(&commits).unwrap();
 		assert_eq!(commits.len(), stats.len());
@@ -153,10 +161,8 @@ mod test {
 		for (author, entry) in commits_per_author.detailed_stats().iter() {
 			println!("Author: {}", author);
 			println!("\ttotal commits: {}", entry.len());
-			let mut k = 0;
-			for stat in entry.iter() {
+			for (k, stat) in entry.iter().enumerate() {
 				println!("\t[{k}] {stat}");
-				k += 1;
 			}
 			println!("-----------------------------------------------");
 		}
@@ -171,7 +177,7 @@ mod test {
 		let commits = repo.list_commits(COMMIT_ARGS.clone()).unwrap();
 		println!("total commits: {}", commits.len());
 		println!("-----------------------------------------------");
-		assert!(commits.len() > 0);
+		assert!(!commits.is_empty());
 
 		let stats = repo.commits_stats(&commits).unwrap();
 		assert_eq!(commits.len(), stats.len());
@@ -179,7 +185,7 @@ mod test {
 
 		ticker.tick();
 		let mut global_stats = commits_per_author.global_stats(SortStatsBy::LinesAdded);
-		global_stats.sort_by(|a, b| b.commits_count.cmp(&a.commits_count));
+		global_stats.sort_by_key(|b| std::cmp::Reverse(b.commits_count));
 
 		println!("generated contributor's stats in {:?}", ticker.tick().0);
 		println!("-----------------------------------------------");
@@ -193,7 +199,7 @@ mod test {
 			let commits_count = global_stat.commits_count;
 			let total_lines = global_stat.stats.lines_added;
 			table.add_row([
-				(&global_stat.author).name.to_string(),
+				global_stat.author.name.to_string(),
 				commits_count.to_string(),
 				total_lines.to_string(),
 			]);
@@ -452,9 +458,9 @@ mod test {
 		for (weekday, hours) in global_stats.iter().enumerate() {
 			for (hour, stats) in hours.iter().enumerate() {
 				let row = rows.get_mut(weekday).unwrap();
-				let current_value = row.get((hour + 1) as usize).unwrap().parse::<usize>().unwrap();
+				let current_value = row.get(hour + 1).unwrap().parse::<usize>().unwrap();
 				let new_value = current_value + stats.commits_count;
-				*row.get_mut((hour + 1) as usize).unwrap() = new_value.to_string();
+				*row.get_mut(hour + 1).unwrap() = new_value.to_string();
 			}
 		}
 		table.add_rows(rows);
@@ -462,40 +468,3311 @@ mod test {
 	}
 
 	#[test]
-	fn test_string_to_author() {
+	fn test_use_mailmap() {
 		init_log();
-		let mut author: Author = "Alessandro Crugnola <alessandro@gmail.com>".try_into().unwrap();
-		println!("Author: {}", author);
+		let fixture = Fixture::new();
+		fixture.git(["config", "log.mailmap", "false"]);
+		fixture.commit("Jane Doe <jane@work.com>", 1704103200, "first", &[("a.txt", "a")]);
+		fixture.commit("jane <jane@home.com>", 1704189600, "second", &[("b.txt", "b")]);
+		fixture.commit(
+			"Jane Doe <jane@work.com>",
+			1704276000,
+			"mailmap",
+			&[(".mailmap", "Jane Doe <jane@work.com> <jane@home.com>\n")],
+		);
+		let repo = fixture.repo();
 
-		author = "Alessandro Crugnola <sephiroth> <alessandro@gmail.com>".try_into().unwrap();
-		println!("Author: {}", author);
+		let args = CommitArgs::builder().author(Author::new("Jane Doe")).build().unwrap();
+		assert_eq!(2, repo.list_commits(args).unwrap().len());
 
-		author = "Alessandro <alessandro.crugnola_123+1@gmail.com>".try_into().unwrap();
-		println!("Author: {}", author);
+		let args = CommitArgs::builder()
+			.author(Author::new("Jane Doe"))
+			.use_mailmap(true)
+			.build()
+			.unwrap();
+		let commits = repo.list_commits(args).unwrap();
+		assert_eq!(3, commits.len());
 
-		author = "Alessandro Crugnola <>".try_into().unwrap();
-		println!("Author: {}", author);
+		let stats = repo.commits_stats(&commits).unwrap();
+		let commits_per_author = stats.commits_per_author();
+		assert_eq!(1, commits_per_author.detailed_stats().len());
+		let global_stats = commits_per_author.global_stats(SortStatsBy::Commits);
+		assert_eq!("Jane Doe <jane@work.com>", global_stats[0].author.to_string());
+
+		// the counts agree with the commit stats, with and without the mailmap'ed author filter
+		let args = |use_mailmap| {
+			CommitArgs::builder().author(Author::new("Jane Doe")).use_mailmap(use_mailmap).build().unwrap()
+		};
+		let counts = repo.commit_counts_per_author(args(true)).unwrap();
+		assert_eq!(vec![(Author::new("Jane Doe").with_email("jane@work.com"), 3)], counts);
+		let counts = repo.commit_counts_per_author(args(false)).unwrap();
+		assert_eq!(vec![(Author::new("Jane Doe").with_email("jane@work.com"), 2)], counts);
 	}
 
-	#[derive(Debug)]
-	struct Ticker {
-		start: Instant,
-		current: Instant,
+	#[test]
+	fn test_co_authored_by_trailers() {
+		init_log();
+		let fixture = Fixture::new();
+		let hash = fixture.commit(
+			"Jane Doe <jane@example.com>",
+			1704103200,
+			"pair work\n\nCo-authored-by: Bob <bob@example.com>\nCo-authored-by: Carl <carl@example.com>",
+			&[("a.txt", "a\n")],
+		);
+		let commit = fixture.repo().commit_stats(hash).unwrap();
+		assert_eq!(
+			vec!["Bob <bob@example.com>", "Carl <carl@example.com>"],
+			commit.co_authors.iter().map(|a| a.to_string()).collect::<Vec<_>>()
+		);
+		assert_eq!(1, commit.stats.lines_added);
+
+		let hash = fixture.commit("Jane Doe <jane@example.com>", 1704189600, "solo work", &[("b.txt", "b\n")]);
+		let commit = fixture.repo().commit_stats(hash).unwrap();
+		assert!(commit.co_authors.is_empty());
 	}
 
-	impl Ticker {
-		pub fn new() -> Self {
-			Ticker {
-				start: Instant::now(),
-				current: Instant::now(),
+	#[test]
+	fn test_max_stat_files() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "small", &[("small.txt", "a\n")]);
+		let files = (0..20).map(|index| (format!("vendor/{index}.txt"), "a\nb\n")).collect::<Vec<_>>();
+		let files = files.iter().map(|(name, content)| (name.as_str(), *content)).collect::<Vec<_>>();
+		let huge = fixture.commit("Jane Doe <jane@example.com>", 1704189600, "huge", &files);
+
+		let args = CommitArgs::builder().max_stat_files(5).build().unwrap();
+		let commits = fixture.repo().list_commit_details(args).unwrap();
+		assert_eq!(2, commits.len());
+
+		assert_eq!(1, commits[0].files.len());
+		assert!(!commits[0].truncated);
+
+		assert_eq!(huge.to_string(), commits[1].hash.to_string());
+		assert!(commits[1].truncated);
+		assert_eq!(5, commits[1].files.len());
+		assert_eq!(20, commits[1].stats.files_changed);
+		assert_eq!(40, commits[1].stats.lines_added);
+
+		let commits = fixture.repo().list_commit_details(CommitArgs::default()).unwrap();
+		assert!(!commits[1].truncated);
+		assert_eq!(20, commits[1].files.len());
+
+		// the per-commit stats honour the cap of the repository
+		let repo = fixture.repo().with_max_stat_files(5);
+		let commit = repo.commit_stats(huge.clone()).unwrap();
+		assert!(commit.truncated);
+		assert_eq!(5, commit.files.len());
+		assert_eq!(20, commit.stats.files_changed);
+		let commits = repo.commit_stats_many(std::slice::from_ref(&huge)).unwrap();
+		assert!(commits[0].truncated);
+		assert_eq!(5, commits[0].files.len());
+		assert_eq!(20, fixture.repo().commit_stats(huge).unwrap().files.len());
+	}
+
+	#[test]
+	fn test_resolve_ref() {
+		init_log();
+		let fixture = Fixture::new();
+		let first = fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "a\n")]);
+		fixture.git(["tag", "-a", "v1.0", "-m", "release 1.0"]);
+		fixture.git(["branch", "release"]);
+		let second = fixture.commit("Jane Doe <jane@example.com>", 1704189600, "second", &[("a.txt", "b\n")]);
+		let repo = fixture.repo();
+
+		assert_eq!(first.to_string(), repo.resolve_ref("v1.0").unwrap().to_string());
+		assert_eq!(first.to_string(), repo.resolve_ref("release").unwrap().to_string());
+		assert_eq!(second.to_string(), repo.resolve_ref("main").unwrap().to_string());
+		assert_eq!(first.to_string(), repo.resolve_ref(&first.to_string()[..8]).unwrap().to_string());
+
+		let err = repo.resolve_ref("missing").unwrap_err();
+		assert!(err.to_string().contains("cannot resolve ref"));
+	}
+
+	#[test]
+	fn test_committer_timestamp() {
+		init_log();
+		let fixture = Fixture::new();
+		let hash = fixture.commit_with_dates(
+			"Jane Doe <jane@example.com>",
+			1704103200,
+			1704362400,
+			"rebased",
+			&[("a.txt", "a\n")],
+		);
+		let commit = fixture.repo().commit_stats(hash).unwrap();
+		assert_eq!(1704103200, commit.author_timestamp);
+		assert_eq!(1704362400, commit.committer_timestamp);
+		assert_eq!(3.0, vec![commit].landing_delay_stats().max_days);
+	}
+
+	#[test]
+	fn test_list_commits_iter() {
+		init_log();
+		let fixture = Fixture::new();
+		for index in 0..5 {
+			fixture.commit(
+				"Jane Doe <jane@example.com>",
+				1704103200 + index * 3600,
+				"commit",
+				&[("a.txt", &index.to_string())],
+			);
+		}
+		let repo = fixture.repo();
+
+		let mut commits = repo
+			.list_commits_iter(CommitArgs::default())
+			.unwrap()
+			.map(|commit| commit.unwrap().to_string())
+			.collect::<Vec<_>>();
+		commits.reverse();
+		let expected = repo
+			.list_commits(CommitArgs::default())
+			.unwrap()
+			.iter()
+			.map(|commit| commit.to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(expected, commits);
+
+		// dropping the iterator early must not hang
+		let first = repo.list_commits_iter(CommitArgs::default()).unwrap().next().unwrap().unwrap();
+		assert_eq!(expected[4], first.to_string());
+	}
+
+	#[test]
+	fn test_streaming_git_failure() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		let repo = fixture.repo();
+		let args = || CommitArgs::builder().target_branch("missing").build().unwrap();
+
+		let commits = repo.list_commits_iter(args()).unwrap().collect::<Vec<_>>();
+		assert_eq!(1, commits.len());
+		let err = commits.into_iter().next().unwrap().unwrap_err();
+		assert!(err.to_string().contains("missing"), "{err}");
+
+		let commits = repo.commit_details_iter(args()).unwrap().collect::<Vec<_>>();
+		assert_eq!(1, commits.len());
+		assert!(commits[0].is_err());
+
+		let mut output: Vec<u8> = Vec::new();
+		assert!(repo.stream_commit_details_json(args(), &mut output).is_err());
+		assert!(output.is_empty());
+	}
+
+	#[test]
+	fn test_commits_by_release() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let first = fixture.commit(author, 1704103200, "first", &[("a.txt", "1")]);
+		let second = fixture.commit(author, 1704106800, "second", &[("a.txt", "2")]);
+		fixture.git(["tag", "v1.0"]);
+		let third = fixture.commit(author, 1704110400, "third", &[("a.txt", "3")]);
+		fixture.git(["tag", "v1.1"]);
+		let fourth = fixture.commit(author, 1704114000, "fourth", &[("a.txt", "4")]);
+		// a later tag pointing to an already released commit must not steal it
+		fixture.git(["tag", "-a", "-m", "backport", "v1.0-backport", second.to_string().as_str()]);
+
+		let releases = fixture.repo().commits_by_release(CommitArgs::default()).unwrap();
+		let names = |release: &str| releases[release].iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+		assert_eq!(3, releases.len());
+		assert_eq!(vec![first.to_string(), second.to_string()], names("v1.0"));
+		assert_eq!(vec![third.to_string()], names("v1.1"));
+		assert_eq!(vec![fourth.to_string()], names("unreleased"));
+	}
+
+	#[test]
+	fn test_commits_stats_with_progress() {
+		init_log();
+		let fixture = Fixture::new();
+		for index in 0..4 {
+			fixture.commit(
+				"Jane Doe <jane@example.com>",
+				1704103200 + index * 3600,
+				"commit",
+				&[("a.txt", &index.to_string())],
+			);
+		}
+		let repo = fixture.repo();
+		let commits = repo.list_commits(CommitArgs::default()).unwrap();
+
+		let progress = std::sync::Mutex::new(Vec::new());
+		let stats = repo
+			.commits_stats_with_progress(&commits, |done, total| progress.lock().unwrap().push((done, total)))
+			.unwrap();
+		assert_eq!(4, stats.len());
+
+		let mut progress = progress.into_inner().unwrap();
+		progress.sort();
+		assert_eq!(vec![(1, 4), (2, 4), (3, 4), (4, 4)], progress);
+	}
+
+	#[test]
+	fn test_commits_stats_with_threads() {
+		init_log();
+		let fixture = Fixture::new();
+		for index in 0..4 {
+			fixture.commit(
+				"Jane Doe <jane@example.com>",
+				1704103200 + index * 3600,
+				"commit",
+				&[("a.txt", &index.to_string())],
+			);
+		}
+		let repo = fixture.repo();
+		let commits = repo.list_commits(CommitArgs::default()).unwrap();
+
+		for threads in [0, 1, 2] {
+			let stats = repo.commits_stats_with_threads(&commits, threads).unwrap();
+			let hashes = stats.iter().map(|commit| commit.hash.to_string()).collect::<Vec<_>>();
+			assert_eq!(commits.iter().map(|commit| commit.to_string()).collect::<Vec<_>>(), hashes);
+		}
+	}
+
+	#[test]
+	fn test_solo_owned_files() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "shared", &[("shared.txt", "1")]);
+		fixture.commit("John Doe <john@example.com>", 1704106800, "shared", &[("shared.txt", "2")]);
+		fixture.commit("Jane Doe <jane@example.com>", 1704110400, "silo", &[("silo.txt", "1")]);
+		fixture.commit("Jane Doe <jane@example.com>", 1704114000, "silo", &[("silo.txt", "2"), ("b.txt", "1")]);
+
+		let silos = fixture.repo().solo_owned_files(CommitArgs::default()).unwrap();
+		assert_eq!(1, silos.len());
+		let jane = Author::new("Jane Doe").with_email("jane@example.com");
+		assert_eq!(&vec!["b.txt".to_string(), "silo.txt".to_string()], silos.get(&jane).unwrap());
+
+		// the truncated commits could hide the other authors of a file
+		let args = CommitArgs::builder().max_stat_files(1).build().unwrap();
+		assert!(fixture.repo().solo_owned_files(args).is_err());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_git_binary() {
+		use std::os::unix::fs::PermissionsExt;
+
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		// a git shim logging its arguments before running the real git
+		let bin = tempfile::tempdir().unwrap();
+		let shim = bin.path().join("git-shim");
+		let log = bin.path().join("calls.log");
+		let git = which::which("git").unwrap();
+		std::fs::write(&shim, format!("#!/bin/sh\necho \"$@\" >> {}\nexec {} \"$@\"\n", log.display(), git.display()))
+			.unwrap();
+		std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+		let repo = fixture.repo().with_git_binary(shim);
+		assert_eq!(1, repo.commits_count().unwrap());
+		assert!(std::fs::read_to_string(&log).unwrap().contains("rev-list --count --all"));
+
+		let repo = fixture.repo().with_git_binary(bin.path().join("missing"));
+		assert!(repo.commits_count().is_err());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_timeout() {
+		use std::os::unix::fs::PermissionsExt;
+
+		init_log();
+		let fixture = Fixture::new();
+		// a fake gpg which signs the commits, but never completes the signature verification.
+		// `log.showSignature` makes every `git log` verify the signatures, hanging git.
+		let gpg = fixture.path().join(".git").join("slow-gpg");
+		std::fs::write(
+			&gpg,
+			"#!/bin/sh\n\
+			case \"$*\" in *--verify*) exec sleep 10;; esac\n\
+			cat >/dev/null\n\
+			echo '[GNUPG:] SIG_CREATED D 1 8 00 0 0' >&2\n\
+			printf -- '-----BEGIN PGP SIGNATURE-----\\n\\nfake\\n-----END PGP SIGNATURE-----\\n'\n",
+		)
+		.unwrap();
+		std::fs::set_permissions(&gpg, std::fs::Permissions::from_mode(0o755)).unwrap();
+		fixture.git(["config", "gpg.program", gpg.to_str().unwrap()]);
+		fixture.git(["config", "user.signingKey", "fake"]);
+		fixture.git(["config", "commit.gpgSign", "true"]);
+		let commit = fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1")]);
+
+		// commands completing in time are not affected
+		let repo = fixture.repo().with_timeout(Duration::from_secs(5));
+		assert_eq!(commit.to_string(), repo.resolve_ref("HEAD").unwrap().to_string());
+		// nor git failing on its own
+		let err = repo.resolve_ref("missing").unwrap_err();
+		assert!(!matches!(err.downcast_ref::<Error>(), Some(Error::Timeout(_))));
+
+		fixture.git(["config", "log.showSignature", "true"]);
+		let repo = fixture.repo().with_timeout(Duration::from_millis(500));
+		let start = Instant::now();
+		let err = repo.list_commits(CommitArgs::default()).unwrap_err();
+		assert!(start.elapsed() < Duration::from_secs(5));
+		assert!(matches!(err.downcast_ref::<Error>(), Some(Error::Timeout(_))));
+	}
+
+	#[test]
+	fn test_merge_commit_stats() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "base", &[("a.txt", "1\n2\n3\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		fixture.commit(author, 1704106800, "feature", &[("a.txt", "1\nfeature\n3\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		fixture.commit(author, 1704110400, "main", &[("a.txt", "1\nmain\n3\n")]);
+
+		// record the merge of the conflicting changes, resolving the conflict by hand
+		fixture.git(["merge", "-q", "--no-ff", "--no-commit", "-s", "ours", "feature"]);
+		let merge = fixture.commit(author, 1704114000, "merge", &[("a.txt", "1\nmain and feature\n3\nextra\n")]);
+		let stats = fixture.repo().merge_commit_stats(&merge).unwrap();
+		assert_eq!(1, stats.files_changed);
+		assert_eq!(2, stats.lines_added);
+		assert_eq!(2, stats.lines_deleted);
+
+		// a trivial merge has an empty combined diff
+		fixture.git(["checkout", "-q", "-b", "other", "HEAD~1"]);
+		fixture.commit(author, 1704117600, "other", &[("b.txt", "b\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		fixture.git_with_env(
+			["merge", "-q", "--no-ff", "--no-edit", "other"],
+			[("GIT_AUTHOR_NAME", "Jane Doe".to_string()), ("GIT_AUTHOR_EMAIL", "jane@example.com".to_string())],
+		);
+		let merge = fixture.repo().resolve_ref("HEAD").unwrap();
+		let stats = fixture.repo().merge_commit_stats(&merge).unwrap();
+		assert_eq!(0, stats.files_changed);
+		assert_eq!(0, stats.lines_added);
+		assert_eq!(0, stats.lines_deleted);
+	}
+
+	#[test]
+	fn test_open_validates_repository() {
+		init_log();
+		let fixture = Fixture::new();
+		assert!(Repo::open(fixture.path()).is_ok());
+
+		let dir = tempfile::tempdir().unwrap();
+		let err = Repo::open(dir.path()).unwrap_err();
+		assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NotARepository(_))));
+
+		let err = Repo::open(&dir.path().join("missing")).unwrap_err();
+		assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NotARepository(_))));
+	}
+
+	#[test]
+	fn test_binary_commit_stats() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "base", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		fixture.commit(author, 1704106800, "feature", &[("b.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		let png = fixture.commit(author, 1704110400, "image", &[("logo.png", "\u{89}PNG\r\n\u{1a}\n\0\0\0\rIHDR")]);
+		fixture.git_with_env(
+			["merge", "-q", "--no-ff", "--no-edit", "feature"],
+			[("GIT_AUTHOR_NAME", "Jane Doe".to_string()), ("GIT_AUTHOR_EMAIL", "jane@example.com".to_string())],
+		);
+		fixture.commit(author, 1704114000, "text", &[("a.txt", "1\n2\n")]);
+		let repo = fixture.repo();
+
+		let detail = repo.commit_stats(png.clone()).unwrap();
+		assert_eq!(1, detail.stats.files_changed);
+		assert_eq!(0, detail.stats.lines_added);
+		assert_eq!(0, detail.stats.lines_deleted);
+		assert_eq!(1, detail.files.len());
+		assert_eq!("logo.png", detail.files[0].path);
+
+		let details = repo.list_commit_details(CommitArgs::default()).unwrap();
+		assert_eq!(5, details.len());
+		let binary = details.iter().find(|commit| commit.hash.to_string() == png.to_string()).unwrap();
+		assert_eq!(1, binary.stats.files_changed);
+		assert_eq!(0, binary.stats.lines_added);
+		// the merge commit has no stats, and doesn't affect the following commit
+		assert_eq!(0, details[3].stats.files_changed);
+		assert!(details[3].files.is_empty());
+		assert_eq!(1, details[4].stats.files_changed);
+		assert_eq!(1, details[4].stats.lines_added);
+		assert_eq!("a.txt", details[4].files[0].path);
+	}
+
+	#[test]
+	fn test_recent() {
+		init_log();
+		let fixture = Fixture::new();
+		let commits = (0..4)
+			.map(|index| {
+				fixture.commit(
+					"Jane Doe <jane@example.com>",
+					1704103200 + index * 3600,
+					"commit",
+					&[("a.txt", &index.to_string())],
+				)
+			})
+			.map(|commit| commit.to_string())
+			.collect::<Vec<_>>();
+		let repo = fixture.repo();
+
+		let recent = repo.recent(2).unwrap();
+		assert_eq!(vec![commits[3].clone(), commits[2].clone()], recent.iter().map(|c| c.hash.to_string()).collect::<Vec<_>>());
+		assert_eq!(1, recent[0].stats.files_changed);
+
+		let recent = repo.recent(10).unwrap();
+		let mut expected = commits.clone();
+		expected.reverse();
+		assert_eq!(expected, recent.iter().map(|c| c.hash.to_string()).collect::<Vec<_>>());
+		assert!(repo.recent(0).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_commits_between() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "first", &[("a.txt", "1")]);
+		fixture.git(["tag", "v1.0"]);
+		let second = fixture.commit(author, 1704106800, "second", &[("a.txt", "2")]);
+		let third = fixture.commit("John Doe <john@example.com>", 1704110400, "third", &[("a.txt", "3")]);
+		fixture.git(["tag", "v1.1"]);
+		fixture.commit(author, 1704114000, "fourth", &[("a.txt", "4")]);
+		let repo = fixture.repo();
+
+		let commits = repo.commits_between("v1.0", "v1.1", CommitArgs::default()).unwrap();
+		assert_eq!(
+			vec![second.to_string(), third.to_string()],
+			commits.iter().map(|c| c.to_string()).collect::<Vec<_>>()
+		);
+
+		let args = CommitArgs::builder().author(Author::new("John Doe")).build().unwrap();
+		let commits = repo.commits_between("v1.0", "v1.1", args).unwrap();
+		assert_eq!(vec![third.to_string()], commits.iter().map(|c| c.to_string()).collect::<Vec<_>>());
+
+		let err = repo.commits_between("v1.0", "v2.0", CommitArgs::default()).unwrap_err();
+		assert!(err.to_string().contains("v2.0"));
+	}
+
+	#[test]
+	fn test_pickaxe() {
+		init_log();
+		let args = CommitArgs::builder().pickaxe("fn parse_commit").build().unwrap();
+		assert!(args.into_iter().any(|arg| arg == "-Sfn parse_commit"));
+		let args = CommitArgs::builder().pickaxe_regex("fn parse_[a-z]+").build().unwrap();
+		assert!(args.into_iter().any(|arg| arg == "-Gfn parse_[a-z]+"));
+		assert!(CommitArgs::builder().pickaxe("a").pickaxe_regex("b").build().is_err());
+
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "first", &[("lib.rs", "fn main() {}\n")]);
+		let introduced = fixture.commit(author, 1704106800, "add", &[("lib.rs", "fn main() {}\nfn helper() {}\n")]);
+		fixture.commit(author, 1704110400, "other", &[("other.rs", "fn other() {}\n")]);
+		let removed = fixture.commit(author, 1704114000, "remove", &[("lib.rs", "fn main() {}\n")]);
+		let repo = fixture.repo();
+
+		let args = CommitArgs::builder().pickaxe("fn helper").build().unwrap();
+		let commits = repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec![introduced.to_string(), removed.to_string()], commits);
+
+		let args = CommitArgs::builder().pickaxe_regex("fn (helper|other)").build().unwrap();
+		assert_eq!(3, repo.list_commits(args).unwrap().len());
+	}
+
+	#[test]
+	fn test_max_count() {
+		init_log();
+		let fixture = Fixture::new();
+		let commits = (0..5)
+			.map(|index| {
+				fixture.commit(
+					"Jane Doe <jane@example.com>",
+					1704103200 + index * 3600,
+					"commit",
+					&[("a.txt", &index.to_string())],
+				)
+			})
+			.map(|commit| commit.to_string())
+			.collect::<Vec<_>>();
+		let repo = fixture.repo();
+
+		let args = CommitArgs::builder().max_count(2).build().unwrap();
+		assert!(args.clone().into_iter().any(|arg| arg == "--max-count=2"));
+		// the newest commits, in chronological order
+		let result = repo.list_commits(args.clone()).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(commits[3..], result);
+		let details = repo.list_commit_details(args).unwrap();
+		assert_eq!(commits[3], details[0].hash.to_string());
+		assert_eq!(2, details.len());
+	}
+
+	#[test]
+	fn test_diff_stats() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "first", &[("a.txt", "1\n2\n3\n")]);
+		fixture.git(["tag", "v1.0"]);
+		fixture.commit(author, 1704106800, "second", &[("a.txt", "1\nb\n3\n4\n"), ("b.txt", "b\n")]);
+		let repo = fixture.repo();
+
+		let stats = repo.diff_stats("v1.0", Some("HEAD")).unwrap();
+		assert_eq!((2, 3, 1), (stats.files_changed, stats.lines_added, stats.lines_deleted));
+
+		// no pending changes
+		let stats = repo.diff_stats("HEAD", None).unwrap();
+		assert_eq!((0, 0, 0), (stats.files_changed, stats.lines_added, stats.lines_deleted));
+
+		std::fs::write(fixture.path().join("b.txt"), "b\nc\nd\n").unwrap();
+		std::fs::write(fixture.path().join("untracked.txt"), "untracked\n").unwrap();
+		let stats = repo.diff_stats("HEAD", None).unwrap();
+		assert_eq!((1, 2, 0), (stats.files_changed, stats.lines_added, stats.lines_deleted));
+		let stats = repo.diff_stats("v1.0", None).unwrap();
+		assert_eq!((2, 5, 1), (stats.files_changed, stats.lines_added, stats.lines_deleted));
+
+		assert!(repo.diff_stats("v2.0", None).is_err());
+	}
+
+	#[test]
+	fn test_multiple_authors() {
+		init_log();
+		let fixture = Fixture::new();
+		let jane = fixture.commit("Jane Doe <jane@example.com>", 1704103200, "jane", &[("a.txt", "1")]);
+		let john = fixture.commit("John Doe <john@example.com>", 1704106800, "john", &[("a.txt", "2")]);
+		fixture.commit("Bob Smith <bob@example.com>", 1704110400, "bob", &[("a.txt", "3")]);
+		let repo = fixture.repo();
+
+		let args = CommitArgs::builder()
+			.authors(vec![Author::new("Jane Doe"), Author::new("John Doe")])
+			.build()
+			.unwrap();
+		assert_eq!(2, args.clone().into_iter().filter(|arg| arg.to_string_lossy().starts_with("--author=")).count());
+		let commits = repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec![jane.to_string(), john.to_string()], commits);
+
+		// the last author replaces the previous one
+		let args = CommitArgs::builder().author(Author::new("Jane Doe")).author(Author::new("Bob Smith")).build();
+		assert_eq!(1, repo.list_commits(args.unwrap()).unwrap().len());
+
+		let args = CommitArgs::builder()
+			.authors(vec![Author::new("Jane Doe"), Author::new("John Doe")])
+			.exclude_author("Bob".to_string())
+			.build();
+		assert!(args.is_err());
+	}
+
+	#[test]
+	fn test_to_git_args() {
+		let args = CommitArgs::builder()
+			.since(1704412800)
+			.exclude_merges(true)
+			.history_scope(HistoryScope::Head)
+			.exclude_path("vendor")
+			.build()
+			.unwrap();
+		let expected = vec!["HEAD", "--since=2024-01-05T00:00:00Z", "--no-merges", "--", ":(exclude)vendor"];
+		assert_eq!(expected, args.to_git_args());
+		// the args are not consumed, and iterating them gives the same result
+		assert_eq!(args.to_git_args(), args.clone().into_iter().collect::<Vec<_>>());
+		assert_eq!(vec!["--all"], CommitArgs::default().to_git_args());
+	}
+
+	#[test]
+	fn test_since_until_relative() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let now = Utc::now().timestamp();
+		let old = fixture.commit(author, now - 30 * 86400, "old", &[("a.txt", "1\n")]);
+		let recent = fixture.commit(author, now - 86400, "recent", &[("a.txt", "2\n")]);
+		let repo = fixture.repo();
+		let list = |args: CommitArgs| repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+		let args = CommitArgs::builder().since_relative("2 weeks ago").build().unwrap();
+		assert_eq!("commit_since:2 weeks ago", args.to_string());
+		assert_eq!(vec![recent.to_string()], list(args));
+		let args = CommitArgs::builder().until_relative("2 weeks ago").build().unwrap();
+		assert_eq!(vec![old.to_string()], list(args));
+		let args = CommitArgs::builder().since(now - 40 * 86400).until_relative("2 weeks ago").build().unwrap();
+		assert_eq!(vec![old.to_string()], list(args));
+
+		assert!(CommitArgs::builder().since(now).since_relative("last month").build().is_err());
+		assert!(CommitArgs::builder().until(now).until_relative("yesterday").build().is_err());
+	}
+
+	#[test]
+	fn test_since_until_datetime() {
+		let date = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+		let datetime = DateTime::from_timestamp(1706954400, 0).unwrap();
+		let args = CommitArgs::builder().since_date(date).until_datetime(datetime).build().unwrap();
+		let args = args.into_iter().map(|arg| arg.to_string_lossy().to_string()).collect::<Vec<_>>();
+		assert!(args.contains(&"--since=2024-01-05T00:00:00Z".to_string()));
+		assert!(args.contains(&"--until=2024-02-03T10:00:00Z".to_string()));
+
+		let args = CommitArgs::builder().since_datetime(datetime).until_date(date).build().unwrap();
+		assert_eq!(
+			format!("{}", CommitArgs::builder().since(1706954400).until(1704412800).build().unwrap()),
+			format!("{}", args)
+		);
+	}
+
+	#[test]
+	fn test_exclude_path() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let mixed = fixture.commit(author, 1704103200, "mixed", &[("src/lib.rs", "1\n"), ("vendor/dep.rs", "1\n")]);
+		let vendored = fixture.commit(author, 1704106800, "vendored", &[("vendor/dep.rs", "2\n"), ("target/out", "2\n")]);
+		let source = fixture.commit(author, 1704110400, "source", &[("src/lib.rs", "2\n")]);
+		let repo = fixture.repo();
+
+		let args = CommitArgs::builder().exclude_path("vendor/").exclude_path("target").max_count(10).build().unwrap();
+		let arguments = args.clone().into_iter().collect::<Vec<_>>();
+		assert_eq!(&["--", ":(exclude)vendor/", ":(exclude)target"], &arguments[arguments.len() - 3..]);
+
+		let commits = repo.list_commits(args.clone()).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec![mixed.to_string(), source.to_string()], commits);
+
+		let details = repo.list_commit_details(args.clone()).unwrap();
+		assert_eq!(2, details.len());
+		assert!(details.iter().all(|commit| commit.hash.to_string() != vendored.to_string()));
+		assert_eq!(1, details[0].stats.files_changed);
+		assert_eq!(vec!["src/lib.rs"], details[0].files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>());
+
+		let newest = repo.list_commits_iter(args).unwrap().next().unwrap().unwrap();
+		assert_eq!(source.to_string(), newest.to_string());
+	}
+
+	#[test]
+	fn test_contributors() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("zoe <zoe@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		fixture.commit("Bob Smith <bob@example.com>", 1704106800, "second", &[("b.txt", "1\n")]);
+		fixture.commit("Bob Smith <BOB@example.com>", 1704110400, "third", &[("b.txt", "2\n")]);
+		fixture.commit("Alice <alice@example.com>", 1704114000, "fourth", &[("c.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		let contributors = repo.contributors(CommitArgs::default()).unwrap();
+		assert_eq!(
+			vec!["alice <alice@example.com>", "bob smith <bob@example.com>", "zoe <zoe@example.com>"],
+			contributors.iter().map(|author| author.to_string().to_lowercase()).collect::<Vec<_>>()
+		);
+
+		let args = CommitArgs::builder().since(1704106800).build().unwrap();
+		let contributors = repo.contributors(args).unwrap();
+		assert_eq!(vec!["Alice", "Bob Smith"], contributors.iter().map(|author| author.name.as_str()).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_commit_counts_per_author() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Bob Smith <bob@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		fixture.commit("Alice <alice@example.com>", 1704106800, "second", &[("b.txt", "1\n")]);
+		fixture.commit("Bob Smith <BOB@example.com>", 1704110400, "third", &[("a.txt", "2\n")]);
+		fixture.commit("Carol <carol@example.com>", 1704114000, "fourth", &[("c.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		let counts = repo.commit_counts_per_author(CommitArgs::default()).unwrap();
+		assert_eq!(
+			vec![("Bob Smith".to_string(), 2), ("Alice".to_string(), 1), ("Carol".to_string(), 1)],
+			counts.iter().map(|(author, count)| (author.name.clone(), *count)).collect::<Vec<_>>()
+		);
+
+		let commits = repo.commits_stats(&repo.list_commits(CommitArgs::default()).unwrap()).unwrap();
+		let expected = commits.commits_per_author();
+		for (author, count) in counts.iter() {
+			assert_eq!(expected.0.get(author).unwrap().len(), *count);
+		}
+
+		let args = CommitArgs::builder().since(1704110400).build().unwrap();
+		let counts = repo.commit_counts_per_author(args).unwrap();
+		assert_eq!(vec!["Bob Smith", "Carol"], counts.iter().map(|(author, _)| author.name.as_str()).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_author_summary() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Bob Smith <bob@example.com>", 1704103200, "first", &[("a.txt", "1\n2\n")]);
+		fixture.commit("Alice <alice@example.com>", 1704106800, "second", &[("b.txt", "1\n")]);
+		fixture.commit("Bob Smith <BOB@example.com>", 1704110400, "third", &[("a.txt", "3\n"), ("c.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		let summary = repo.author_summary(CommitArgs::default()).unwrap();
+		assert_eq!(2, summary.len());
+		assert_eq!("Bob Smith", summary[0].author.name);
+		assert_eq!(2, summary[0].commits_count);
+		assert_eq!(1704103200, summary[0].first_commit);
+		assert_eq!(1704110400, summary[0].last_commit);
+		assert_eq!(
+			CommitStats {
+				files_changed: 3,
+				lines_added: 4,
+				lines_deleted: 2,
+			},
+			summary[0].stats
+		);
+		assert_eq!("Alice", summary[1].author.name);
+		assert_eq!(1, summary[1].commits_count);
+		assert_eq!(1704106800, summary[1].first_commit);
+		assert_eq!(1704106800, summary[1].last_commit);
+
+		let args = CommitArgs::builder().since(1704106800).build().unwrap();
+		let summary = repo.author_summary(args).unwrap();
+		assert_eq!(vec![1, 1], summary.iter().map(|item| item.commits_count).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_fetch_remote() {
+		init_log();
+		let upstream = Fixture::new();
+		upstream.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		upstream.git(["branch", "feature"]);
+		let fixture = Fixture::new();
+		fixture.git(["remote".as_ref(), "add".as_ref(), "upstream".as_ref(), upstream.path().as_os_str()]);
+		let repo = fixture.repo();
+
+		repo.fetch_remote("upstream", false).unwrap();
+		assert!(repo.resolve_ref("upstream/feature").is_ok());
+
+		upstream.git(["branch", "-D", "feature"]);
+		repo.fetch_remote("upstream", false).unwrap();
+		assert!(repo.resolve_ref("upstream/feature").is_ok());
+		repo.fetch_remote("upstream", true).unwrap();
+		assert!(repo.resolve_ref("upstream/feature").is_err());
+		assert!(repo.resolve_ref("upstream/main").is_ok());
+
+		let err = repo.fetch_remote("missing", false).unwrap_err();
+		let message = err.to_string();
+		// the git error message follows the prefix
+		assert!(message.strip_prefix("failed to fetch remote `missing`: ").is_some_and(|s| !s.is_empty()), "{message}");
+	}
+
+	#[cfg(feature = "async")]
+	#[tokio::test]
+	async fn test_async() {
+		init_log();
+		let upstream = Fixture::new();
+		upstream.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n2\n")]);
+		upstream.commit("John Roe <john@example.com>", 1704106800, "second", &[("a.txt", "1\n"), ("b.txt", "3\n")]);
+		let fixture = Fixture::new();
+		fixture.git(["remote".as_ref(), "add".as_ref(), "origin".as_ref(), upstream.path().as_os_str()]);
+		let repo = fixture.repo();
+
+		repo.fetch_async().await.unwrap();
+		let args = CommitArgs::builder().target_branch("origin/main").build().unwrap();
+		let commits = repo.list_commits_async(args.clone()).await.unwrap();
+		let hashes = |commits: &[CommitHash]| commits.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(2, commits.len());
+		assert_eq!(hashes(&repo.list_commits(args).unwrap()), hashes(&commits));
+
+		let expected = repo.commits_stats(&commits).unwrap();
+		let stats = repo.commits_stats_async(&commits).await.unwrap();
+		assert_eq!(expected.len(), stats.len());
+		for (expected, actual) in expected.iter().zip(stats.iter()) {
+			assert_eq!(expected.hash.to_string(), actual.hash.to_string());
+			assert_eq!(expected.author, actual.author);
+			assert_eq!(expected.stats, actual.stats);
+		}
+		assert!(repo.commits_stats_async(&[]).await.unwrap().is_empty());
+
+		fixture.git(["remote", "set-url", "origin", "/missing/repo"]);
+		assert!(repo.fetch_async().await.is_err());
+	}
+
+	#[test]
+	fn test_status() {
+		init_log();
+		let fixture = Fixture::new();
+		let repo = fixture.repo();
+		assert!(!repo.is_dirty().unwrap());
+
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n"), ("b.txt", "1\n")]);
+		assert!(!repo.is_dirty().unwrap());
+		assert_eq!(WorkTreeStatus::default(), repo.status().unwrap());
+
+		std::fs::write(fixture.path().join("untracked.txt"), "1\n").unwrap();
+		assert!(repo.is_dirty().unwrap());
+
+		// a.txt is staged and modified again, b.txt is only modified, c.txt is a new staged file
+		std::fs::write(fixture.path().join("a.txt"), "2\n").unwrap();
+		std::fs::write(fixture.path().join("c.txt"), "1\n").unwrap();
+		fixture.git(["add", "a.txt", "c.txt"]);
+		std::fs::write(fixture.path().join("a.txt"), "3\n").unwrap();
+		std::fs::write(fixture.path().join("b.txt"), "2\n").unwrap();
+		assert_eq!(
+			WorkTreeStatus {
+				staged: 2,
+				modified: 2,
+				untracked: 1,
+				conflicted: 0,
+			},
+			repo.status().unwrap()
+		);
+
+		let missing = tempfile::tempdir().unwrap();
+		assert!(Repo::new(missing.path()).is_dirty().is_err());
+	}
+
+	#[test]
+	fn test_conflicted_status() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "first", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		fixture.commit(author, 1704106800, "feature", &[("a.txt", "2\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		fixture.commit(author, 1704110400, "main", &[("a.txt", "3\n")]);
+		// the merge stops with a conflict on a.txt, `Fixture::git` would fail on the exit code
+		let merge = Command::new("git")
+			.arg("-C")
+			.arg(fixture.path())
+			.args(["merge", "-q", "--no-edit", "feature"])
+			.env("GIT_CONFIG_NOSYSTEM", "1")
+			.env("GIT_COMMITTER_NAME", "Committer")
+			.env("GIT_COMMITTER_EMAIL", "committer@example.com")
+			.env("GIT_AUTHOR_NAME", "Jane Doe")
+			.env("GIT_AUTHOR_EMAIL", "jane@example.com")
+			.output()
+			.unwrap();
+		assert!(String::from_utf8_lossy(&merge.stdout).contains("CONFLICT"), "{merge:?}");
+
+		let status = fixture.repo().status().unwrap();
+		assert_eq!(1, status.conflicted);
+		assert_eq!(0, status.staged + status.modified + status.untracked);
+	}
+
+	#[test]
+	fn test_loc() {
+		init_log();
+		let fixture = Fixture::new();
+		let files = [
+			("src/main.rs", "fn main() {\n\tprintln!(\"a:b\");\n}\n"),
+			("src/lib.rs", "pub mod a;\npub mod b;"),
+			("README", "readme\n"),
+			("vendor/dep.rs", "1\n2\n3\n4\n5\n"),
+			("image.png", "\0\x01\n\x02\n"),
+			("empty.txt", ""),
+		];
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &files);
+		// uncommitted changes are not counted
+		std::fs::write(fixture.path().join("src/main.rs"), "1\n2\n3\n4\n5\n6\n").unwrap();
+		let repo = fixture.repo();
+
+		let loc = repo.loc(CommitArgs::default()).unwrap();
+		assert_eq!(Some(&10), loc.get("rs"));
+		assert_eq!(Some(&1), loc.get(""));
+		assert_eq!(None, loc.get("png"));
+		assert_eq!(None, loc.get("txt"));
+
+		let args = CommitArgs::builder().exclude_path("vendor").build().unwrap();
+		assert_eq!(Some(&5), repo.loc(args).unwrap().get("rs"));
+	}
+
+	#[test]
+	fn test_exclude_generated() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let files = [
+			(".gitattributes", "schema.rs linguist-generated=true\n*.dat binary\n"),
+			("src/main.rs", "1\n2\n"),
+			("schema.rs", "1\n2\n3\n"),
+			("assets/app.min.js", "1\n"),
+			("image.dat", "1\n"),
+		];
+		fixture.commit(author, 1704103200, "first", &files);
+		fixture.commit(author, 1704106800, "lock", &[("crates/cli/Cargo.lock", "1\n2\n3\n4\n")]);
+		fixture.commit(author, 1704110400, "third", &[("src/main.rs", "1\n2\n3\n"), ("Cargo.lock", "1\n")]);
+		let repo = fixture.repo();
+
+		let args = CommitArgs::builder().exclude_generated(true).build().unwrap();
+		let commits = repo.list_commit_details(args.clone()).unwrap();
+		assert_eq!(vec![1704103200, 1704110400], commits.iter().map(|c| c.author_timestamp).collect::<Vec<_>>());
+		assert_eq!(
+			vec![".gitattributes", "src/main.rs"],
+			commits[0].files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>()
+		);
+		assert_eq!(
+			CommitStats {
+				files_changed: 2,
+				lines_added: 4,
+				lines_deleted: 0,
+			},
+			commits[0].stats
+		);
+		assert_eq!(vec!["src/main.rs"], commits[1].files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>());
+		assert_eq!(2, repo.list_commits(args.clone()).unwrap().len());
+		assert_eq!(Some(&3), repo.loc(args.clone()).unwrap().get("rs"));
+		assert!(repo.file_history(Path::new("src/main.rs"), args, false).is_err());
+
+		assert_eq!(3, repo.list_commits(CommitArgs::default()).unwrap().len());
+	}
+
+	#[test]
+	fn test_renames() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let content = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+		let files = [("a.txt", content), ("c.txt", content), ("src/old/lib.rs", content)];
+		fixture.commit(author, 1704103200, "first", &files);
+		fixture.git(["mv", "a.txt", "b.txt"]);
+		std::fs::create_dir(fixture.path().join("docs")).unwrap();
+		fixture.git(["mv", "c.txt", "docs/c.txt"]);
+		fixture.git(["mv", "src/old", "src/new"]);
+		let moved = fixture.commit(author, 1704106800, "move", &[("b.txt", &format!("{content}11\n"))]);
+		fixture.commit(author, 1704110400, "edit", &[("b.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		let commits = repo.list_commit_details(CommitArgs::default()).unwrap();
+		assert_eq!(3, commits.len());
+		assert!(commits[0].renames.is_empty());
+		assert_eq!(
+			vec![
+				("a.txt".to_string(), "b.txt".to_string()),
+				("c.txt".to_string(), "docs/c.txt".to_string()),
+				("src/old/lib.rs".to_string(), "src/new/lib.rs".to_string())
+			],
+			commits[1].renames
+		);
+		assert_eq!(
+			vec![("b.txt", 1, 0), ("docs/c.txt", 0, 0), ("src/new/lib.rs", 0, 0)],
+			commits[1].files.iter().map(|f| (f.path.as_str(), f.lines_added, f.lines_deleted)).collect::<Vec<_>>()
+		);
+		assert!(commits[2].renames.is_empty());
+
+		// with a line added, `b.txt` is not similar enough to `a.txt` to be a rename
+		let strict = CommitArgs::builder().rename_threshold(95).build().unwrap();
+		let commits = repo.list_commit_details(strict).unwrap();
+		assert_eq!(4, commits[1].files.len());
+		assert_eq!(2, commits[1].renames.len());
+		assert!(!commits[1].renames.iter().any(|(from, _)| from == "a.txt"));
+
+		let renames_only = CommitArgs::builder().renames_only(true).build().unwrap();
+		let commits = repo.list_commits(renames_only).unwrap();
+		assert_eq!(vec![moved.to_string()], commits.iter().map(|c| c.to_string()).collect::<Vec<_>>());
+
+		assert!(CommitArgs::builder().rename_threshold(101).build().is_err());
+	}
+
+	#[test]
+	fn test_parse_numstat_past_u32_max() {
+		let hash = "0123456789abcdef0123456789abcdef01234567";
+		let output = format!("\x1e{hash}\nJane Doe\njane@example.com\n1704103200\n1704103200\n\n5000000000\t1\ta.txt\n");
+		let commits = parse_commit_details(&output, None).unwrap();
+		assert_eq!(5_000_000_000, commits[0].files[0].lines_added);
+		assert_eq!(1, commits[0].files[0].lines_deleted);
+	}
+
+	#[test]
+	fn test_parse_truncated_output() {
+		let hash = "0123456789abcdef0123456789abcdef01234567";
+		let complete = format!("\x1e{hash}\nJane Doe\njane@example.com\n1704103200\n1704106800\n\n1\t0\ta.txt\n");
+		let commits = parse_commit_details(&complete, None).unwrap();
+		assert_eq!(1, commits.len());
+		assert_eq!(1704106800, commits[0].committer_timestamp);
+		assert_eq!(1, commits[0].stats.lines_added);
+		assert!(parse_commit_details("", None).unwrap().is_empty());
+
+		let parse_error = |output: String| {
+			let err = parse_commit_details(&output, None).unwrap_err();
+			match err.downcast_ref::<Error>() {
+				Some(Error::Parse(message)) => message.clone(),
+				_ => panic!("unexpected error: {err}"),
 			}
+		};
+		assert_eq!("author name not found", parse_error(format!("\x1e{hash}\n")));
+		assert_eq!("author timestamp not found", parse_error(format!("\x1e{hash}\nJane Doe\njane@example.com\n")));
+		assert_eq!(
+			"committer timestamp not found",
+			parse_error(format!("\x1e{hash}\nJane Doe\njane@example.com\n1704103200"))
+		);
+		assert_eq!(
+			"invalid author timestamp: `fatal: bad object`",
+			parse_error(format!("\x1e{hash}\nJane Doe\njane@example.com\nfatal: bad object\n1704106800\n"))
+		);
+		assert_eq!("commit hash not found", parse_error("\x1e\nJane Doe\n".to_string()));
+	}
+
+	#[test]
+	fn test_commit_exists() {
+		init_log();
+		let fixture = Fixture::new();
+		let hash = fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		assert!(repo.commit_exists(&hash).unwrap());
+		assert!(repo.commit_exists(&CommitHash::try_from(hash.short(7)).unwrap()).unwrap());
+		let unknown = CommitHash::try_from("0123456789abcdef0123456789abcdef01234567").unwrap();
+		assert!(!repo.commit_exists(&unknown).unwrap());
+		let tree = fixture.git(["rev-parse", "HEAD^{tree}"]);
+		assert!(!repo.commit_exists(&CommitHash::try_from(tree.trim()).unwrap()).unwrap());
+
+		assert_eq!(hash.to_string(), repo.commit_stats(hash.clone()).unwrap().hash.to_string());
+		let err = repo.commit_stats(unknown).unwrap_err();
+		assert!(matches!(err.downcast_ref::<Error>(), Some(Error::UnknownCommit(_))));
+	}
+
+	#[test]
+	fn test_language_breakdown() {
+		init_log();
+		let fixture = Fixture::new();
+		let files = [
+			("src/main.rs", "1\n2\n3\n"),
+			("src/lib.RS", "1\n2\n"),
+			("script.py", "1\n"),
+			("build.zig", "1\n"),
+			("Makefile", "1\n"),
+			("Cargo.lock", "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n"),
+			("empty.c", ""),
+		];
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &files);
+		let repo = fixture.repo();
+
+		// the generated files are skipped, the unknown extensions are kept as they are
+		let languages = repo.language_breakdown().unwrap();
+		assert_eq!(
+			vec![("Rust", 0.625), ("", 0.125), ("Python", 0.125), ("zig", 0.125)],
+			languages.iter().map(|(language, share)| (language.as_str(), *share)).collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn test_commit_stats_many() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let first = fixture.commit(author, 1704103200, "first", &[("a.txt", "1\n2\n")]);
+		let second = fixture.commit(author, 1704106800, "second", &[("a.txt", "1\n"), ("b.txt", "1\n2\n3\n")]);
+		let third = fixture.commit(author, 1704110400, "third", &[("c.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		let short = CommitHash::try_from(first.short(7)).unwrap();
+		let hashes = vec![third.clone(), short.clone(), second.clone()];
+		let commits = repo.commit_stats_many(&hashes).unwrap();
+		assert_eq!(
+			hashes.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+			commits.iter().map(|c| c.hash.to_string()).collect::<Vec<_>>()
+		);
+		for commit in commits.iter() {
+			assert_eq!(repo.commit_stats(commit.hash.clone()).unwrap().stats, commit.stats);
 		}
+		assert_eq!(2, commits[2].stats.files_changed);
+		assert_eq!(3, commits[2].stats.lines_added);
+		assert_eq!(1, commits[2].stats.lines_deleted);
 
-		pub fn tick(&mut self) -> (Duration, Duration) {
-			let elapsed = self.current.elapsed();
-			let total = self.start.elapsed();
-			self.current = Instant::now();
-			(elapsed, total)
+		assert!(repo.commit_stats_many(&[]).unwrap().is_empty());
+		let unknown = CommitHash::try_from("0123456789abcdef0123456789abcdef01234567").unwrap();
+		assert!(repo.commit_stats_many(&[first, unknown]).is_err());
+	}
+
+	#[test]
+	fn test_default_branch() {
+		init_log();
+		let fixture = Fixture::new();
+		let repo = fixture.repo();
+		// no commits, the branch doesn't exist yet
+		assert_eq!(None, repo.default_branch().unwrap());
+
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		assert_eq!(Some("main".to_string()), repo.default_branch().unwrap());
+		fixture.git(["branch", "-m", "master"]);
+		assert_eq!(Some("master".to_string()), repo.default_branch().unwrap());
+		fixture.git(["branch", "-m", "trunk"]);
+		assert_eq!(None, repo.default_branch().unwrap());
+
+		// the branch of the origin HEAD wins over the local branches
+		let upstream = Fixture::new();
+		upstream.commit("Jane Doe <jane@example.com>", 1704106800, "upstream", &[("b.txt", "1\n")]);
+		upstream.git(["branch", "-m", "develop"]);
+		fixture.git(["branch", "main"]);
+		fixture.git(["remote".as_ref(), "add".as_ref(), "origin".as_ref(), upstream.path().as_os_str()]);
+		fixture.git(["fetch", "-q", "origin"]);
+		assert_eq!(Some("main".to_string()), repo.default_branch().unwrap());
+		fixture.git(["remote", "set-head", "origin", "--auto"]);
+		assert_eq!(Some("origin/develop".to_string()), repo.default_branch().unwrap());
+	}
+
+	#[test]
+	fn test_tag_cadence() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let repo = fixture.repo();
+		assert!(repo.tag_cadence().unwrap().is_empty());
+
+		// 2024-01-01, 2024-01-03 and 2024-01-10 12:00
+		fixture.commit(author, 1704067200, "first", &[("a.txt", "1\n")]);
+		fixture.git(["tag", "v1.0"]);
+		assert!(repo.tag_cadence().unwrap().is_empty());
+		fixture.commit(author, 1704153600, "second", &[("a.txt", "2\n")]);
+		fixture.commit(author, 1704240000, "third", &[("a.txt", "3\n")]);
+		fixture.git(["tag", "v1.1"]);
+		fixture.commit(author, 1704888000, "fourth", &[("a.txt", "4\n")]);
+		fixture.git(["tag", "v2.0"]);
+
+		let expected = vec![
+			TagInterval {
+				from: "v1.0".to_string(),
+				to: "v1.1".to_string(),
+				days: 2.0,
+				commits: 2,
+			},
+			TagInterval {
+				from: "v1.1".to_string(),
+				to: "v2.0".to_string(),
+				days: 7.5,
+				commits: 1,
+			},
+		];
+		assert_eq!(expected, repo.tag_cadence().unwrap());
+	}
+
+	#[test]
+	fn test_list_remotes() {
+		init_log();
+		let fixture = Fixture::new();
+		let repo = fixture.repo();
+		assert!(repo.list_remotes().unwrap().is_empty());
+
+		fixture.git(["remote", "add", "origin", "https://example.com/origin.git"]);
+		fixture.git(["remote", "add", "fork", "https://example.com/fork.git"]);
+		fixture.git(["remote", "set-url", "--push", "fork", "git@example.com:fork.git"]);
+		assert_eq!(
+			vec![
+				Remote {
+					name: "fork".to_string(),
+					fetch_url: "https://example.com/fork.git".to_string(),
+					push_url: Some("git@example.com:fork.git".to_string()),
+				},
+				Remote {
+					name: "origin".to_string(),
+					fetch_url: "https://example.com/origin.git".to_string(),
+					push_url: None,
+				},
+			],
+			repo.list_remotes().unwrap()
+		);
+	}
+
+	#[test]
+	fn test_merge_diff_mode() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "base", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		fixture.commit(author, 1704106800, "feature", &[("b.txt", "1\n2\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		fixture.commit(author, 1704110400, "main", &[("c.txt", "1\n2\n3\n")]);
+		fixture.git_with_env(
+			["merge", "-q", "--no-ff", "--no-edit", "feature"],
+			[("GIT_AUTHOR_NAME", "Jane Doe".to_string()), ("GIT_AUTHOR_EMAIL", "jane@example.com".to_string())],
+		);
+		let merge = fixture.repo().resolve_ref("HEAD").unwrap();
+		let args = || CommitArgs::builder().max_count(1).build().unwrap();
+
+		// by default merges have empty stats
+		let repo = fixture.repo();
+		assert_eq!(0, repo.commit_stats(merge.clone()).unwrap().stats.files_changed);
+		let details = repo.list_commit_details(args()).unwrap();
+		assert_eq!(0, details[0].stats.files_changed);
+		assert!(details[0].files.is_empty());
+
+		// the first parent is main: the merge brings in b.txt
+		let repo = fixture.repo().with_merge_diff_mode(MergeDiffMode::FirstParent);
+		let stats = repo.commit_stats(merge.clone()).unwrap();
+		assert_eq!((1, 2), (stats.stats.files_changed, stats.stats.lines_added));
+		assert_eq!(vec!["b.txt"], stats.files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>());
+		let details = repo.list_commit_details(args()).unwrap();
+		assert_eq!(1, details.len());
+		assert_eq!(2, details[0].stats.lines_added);
+
+		// against each parent: b.txt from main, c.txt from feature
+		let repo = fixture.repo().with_merge_diff_mode(MergeDiffMode::Separate);
+		let stats = repo.commit_stats(merge.clone()).unwrap();
+		assert_eq!(merge.to_string(), stats.hash.to_string());
+		assert_eq!((2, 5), (stats.stats.files_changed, stats.stats.lines_added));
+		assert_eq!(vec!["b.txt", "c.txt"], stats.files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>());
+		let details = repo.list_commit_details(args()).unwrap();
+		assert_eq!(1, details.len());
+		assert_eq!(5, details[0].stats.lines_added);
+
+		// the other commits are not affected
+		let all = repo.list_commit_details(CommitArgs::default()).unwrap();
+		assert_eq!(4, all.len());
+		assert_eq!(vec![1, 2, 3, 5], all.iter().map(|c| c.stats.lines_added).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_first_parent() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let base = fixture.commit(author, 1704103200, "base", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		let feature = fixture.commit(author, 1704106800, "feature", &[("b.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		let main = fixture.commit(author, 1704110400, "main", &[("c.txt", "1\n")]);
+		fixture.git_with_env(
+			["merge", "-q", "--no-ff", "--no-edit", "feature"],
+			[("GIT_AUTHOR_NAME", "Jane Doe".to_string()), ("GIT_AUTHOR_EMAIL", "jane@example.com".to_string())],
+		);
+		let merge = fixture.repo().resolve_ref("HEAD").unwrap();
+		let repo = fixture.repo();
+		let list = |args: CommitArgs| repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+		let all = list(CommitArgs::default());
+		assert_eq!(4, all.len());
+		assert!(all.contains(&feature.to_string()));
+
+		let args = CommitArgs::builder().target_branch("main").first_parent(true).build().unwrap();
+		assert!(args.to_string().contains("first_parent:true"));
+		assert_eq!(vec![base.to_string(), main.to_string(), merge.to_string()], list(args));
+
+		let args = CommitArgs::builder().target_branch("main").first_parent(true).exclude_merges(true).build().unwrap();
+		assert_eq!(vec![base.to_string(), main.to_string()], list(args));
+
+		let args = CommitArgs::builder().target_branch("main").first_parent(true).build().unwrap();
+		let details = repo.list_commit_details(args).unwrap();
+		assert_eq!(3, details.len());
+		assert_eq!(0, details[2].stats.files_changed);
+	}
+
+	#[test]
+	fn test_history_scope() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let base = fixture.commit(author, 1704103200, "base", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "spike"]);
+		let spike = fixture.commit(author, 1704106800, "abandoned", &[("b.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		let main = fixture.commit(author, 1704110400, "main", &[("c.txt", "1\n")]);
+		let repo = fixture.repo();
+		let list = |args: CommitArgs| repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		let count = |args: CommitArgs| repo.commit_counts_per_author(args).unwrap()[0].1;
+
+		assert_eq!(3, list(CommitArgs::default()).len());
+		assert_eq!(3, count(CommitArgs::default()));
+
+		let head = CommitArgs::builder().history_scope(HistoryScope::Head).build().unwrap();
+		assert_eq!("scope:HEAD", head.to_string());
+		assert_eq!(vec![base.to_string(), main.to_string()], list(head.clone()));
+		assert_eq!(2, count(head.clone()));
+
+		let branch = CommitArgs::builder().history_scope(HistoryScope::Branch("spike".to_string())).build().unwrap();
+		assert_eq!("target_branch:spike", branch.to_string());
+		assert_eq!(vec![base.to_string(), spike.to_string()], list(branch.clone()));
+		assert_eq!(2, count(branch));
+
+		// HEAD follows the checked out branch
+		fixture.git(["checkout", "-q", "spike"]);
+		assert_eq!(vec![base.to_string(), spike.to_string()], list(head));
+	}
+
+	#[test]
+	fn test_commit_details_iter() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "first", &[("a.txt", "1\n")]);
+		fixture.commit(author, 1704106800, "second", &[("b.txt", "1\n2\n"), ("c.txt", "1\n")]);
+		fixture.commit(author, 1704110400, "third", &[("a.txt", "2\n3\n")]);
+		let repo = fixture.repo();
+
+		let mut expected = repo.list_commit_details(CommitArgs::default()).unwrap();
+		expected.reverse();
+		let mut iter = repo.commit_details_iter(CommitArgs::default()).unwrap();
+		// the first commit is available without reading the whole log
+		let newest = iter.next().unwrap().unwrap();
+		assert_eq!(expected[0].hash.to_string(), newest.hash.to_string());
+		assert_eq!(expected[0].stats, newest.stats);
+		let rest = iter.collect::<anyhow::Result<Vec<_>>>().unwrap();
+		assert_eq!(
+			expected[1..].iter().map(|c| (c.hash.to_string(), c.stats, c.files.len())).collect::<Vec<_>>(),
+			rest.iter().map(|c| (c.hash.to_string(), c.stats, c.files.len())).collect::<Vec<_>>()
+		);
+
+		let args = CommitArgs::builder().author_date_range(1704103200, 1704106800).build().unwrap();
+		let commits = repo.commit_details_iter(args).unwrap().collect::<anyhow::Result<Vec<_>>>().unwrap();
+		assert_eq!(vec![1704106800, 1704103200], commits.iter().map(|c| c.author_timestamp).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_stream_commit_details_json() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit(author, 1704103200, "base", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		fixture.commit(author, 1704106800, "feature", &[("b.txt", "1\n2\n"), ("c.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		fixture.commit(author, 1704110400, "main", &[("d.txt", "1\n2\n3\n")]);
+		fixture.git_with_env(
+			["merge", "-q", "--no-ff", "--no-edit", "feature"],
+			[("GIT_AUTHOR_NAME", "Jane Doe".to_string()), ("GIT_AUTHOR_EMAIL", "jane@example.com".to_string())],
+		);
+
+		for mode in [MergeDiffMode::Off, MergeDiffMode::Separate] {
+			let repo = fixture.repo().with_merge_diff_mode(mode);
+			let mut output: Vec<u8> = Vec::new();
+			let count = repo.stream_commit_details_json(CommitArgs::default(), &mut output).unwrap();
+			let streamed = String::from_utf8(output)
+				.unwrap()
+				.lines()
+				.map(|line| serde_json::from_str::<CommitDetail>(line).unwrap())
+				.collect::<Vec<_>>();
+			assert_eq!(4, count);
+			assert_eq!(4, streamed.len());
+
+			let mut expected = repo.list_commit_details(CommitArgs::default()).unwrap();
+			expected.reverse();
+			for (streamed, expected) in streamed.iter().zip(expected.iter()) {
+				assert_eq!(expected.hash.to_string(), streamed.hash.to_string());
+				assert_eq!(expected.author, streamed.author);
+				assert_eq!(expected.stats, streamed.stats);
+				assert_eq!(expected.files.len(), streamed.files.len());
+			}
+		}
+
+		let args = CommitArgs::builder().max_stat_files(1).max_count(2).build().unwrap();
+		let mut output: Vec<u8> = Vec::new();
+		let repo = fixture.repo().with_merge_diff_mode(MergeDiffMode::Separate);
+		assert_eq!(2, repo.stream_commit_details_json(args, &mut output).unwrap());
+		let output = String::from_utf8(output).unwrap();
+		let merge = serde_json::from_str::<CommitDetail>(output.lines().next().unwrap()).unwrap();
+		assert_eq!(3, merge.stats.files_changed);
+		assert_eq!(1, merge.files.len());
+		assert!(merge.truncated);
+	}
+
+	#[test]
+	fn test_file_history() {
+		init_log();
+		let fixture = Fixture::new();
+		let jane = "Jane Doe <jane@example.com>";
+		let john = "John Doe <john@example.com>";
+		let content = "line 1\nline 2\nline 3\nline 4\nline 5\n";
+		let created = fixture.commit(jane, 1704103200, "create", &[("old.txt", content), ("other.txt", "1\n")]);
+		fixture.git(["mv", "old.txt", "new.txt"]);
+		let renamed = fixture.commit(john, 1704106800, "rename", &[("other.txt", "2\n")]);
+		let edited = fixture.commit(john, 1704110400, "edit", &[("new.txt", &format!("{content}line 6\n"))]);
+		fixture.commit(jane, 1704114000, "other", &[("other.txt", "3\n")]);
+		let repo = fixture.repo();
+		let hashes = |commits: &[CommitDetail]| commits.iter().map(|c| c.hash.to_string()).collect::<Vec<_>>();
+
+		let history = repo.file_history(Path::new("new.txt"), CommitArgs::default(), true).unwrap();
+		assert_eq!(vec![created.to_string(), renamed.to_string(), edited.to_string()], hashes(&history));
+		// only the lines of the file are counted
+		assert_eq!(1, history[0].stats.files_changed);
+		assert_eq!(5, history[0].stats.lines_added);
+		assert_eq!(1, history[2].stats.lines_added);
+		assert_eq!("John Doe", history[2].author.name);
+
+		let history = repo.file_history(Path::new("new.txt"), CommitArgs::default(), false).unwrap();
+		assert_eq!(vec![renamed.to_string(), edited.to_string()], hashes(&history));
+
+		let args = CommitArgs::builder().author(Author::new("John Doe")).build().unwrap();
+		let history = repo.file_history(Path::new("new.txt"), args, true).unwrap();
+		assert_eq!(vec![renamed.to_string(), edited.to_string()], hashes(&history));
+
+		let args = CommitArgs::builder().exclude_path("vendor").build().unwrap();
+		assert!(repo.file_history(Path::new("new.txt"), args, true).is_err());
+	}
+
+	#[test]
+	fn test_details() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		fixture.commit_with_dates(author, 1704103200, 1704189600, "first", &[("a.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "-b", "feature"]);
+		fixture.commit(author, 1704106800, "feature", &[("b.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		fixture.commit(author, 1704200000, "main", &[("c.txt", "1\n")]);
+		fixture.git_with_env(
+			["merge", "-q", "--no-ff", "--no-edit", "feature"],
+			[
+				("GIT_AUTHOR_NAME", "Jane Doe".to_string()),
+				("GIT_AUTHOR_EMAIL", "jane@example.com".to_string()),
+				("GIT_AUTHOR_DATE", "1704300000 +0000".to_string()),
+			],
+		);
+		fixture.git(["checkout", "-q", "-b", "unmerged"]);
+		fixture.commit(author, 1704400000, "unmerged", &[("d.txt", "1\n")]);
+		fixture.git(["checkout", "-q", "main"]);
+		let repo = fixture.repo();
+
+		let now = std::time::Instant::now();
+		let details = repo.details().unwrap();
+		let elapsed = now.elapsed();
+
+		// the same values computed with the individual commands
+		let now = std::time::Instant::now();
+		let first_commit = repo.first_commit().unwrap().map(|c| c.author_timestamp);
+		let last_commit = repo.last_commit().unwrap().map(|c| c.author_timestamp);
+		let commits_count = repo.commits_count().unwrap();
+		let size = repo.size().unwrap();
+		println!("details: {elapsed:?}, individual commands: {:?}", now.elapsed());
+
+		assert_eq!(Some(1704103200), first_commit);
+		assert_eq!(Some(1704300000), last_commit);
+		assert_eq!(5, commits_count);
+		assert_eq!(first_commit, details.first_commit);
+		assert_eq!(last_commit, details.last_commit);
+		assert_eq!(commits_count, details.commits_count);
+		assert_eq!(size, details.size);
+	}
+
+	#[test]
+	fn test_blame_summary() {
+		init_log();
+		let fixture = Fixture::new();
+		fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n2\n3\n4\n")]);
+		fixture.commit("John Doe <john@example.com>", 1704106800, "second", &[("a.txt", "1\nfoo\n3\n4\n5\n")]);
+		std::fs::write(fixture.path().join("a.txt"), "1\nfoo\n3\n4\n5\nuncommitted\n").unwrap();
+		let repo = fixture.repo();
+
+		let summary = repo.blame_summary(Path::new("a.txt")).unwrap();
+		assert_eq!(2, summary.len());
+		assert_eq!(3, summary[&Author::new("Jane Doe").with_email("jane@example.com")]);
+		assert_eq!(2, summary[&Author::new("John Doe").with_email("john@example.com")]);
+
+		assert!(repo.blame_summary(Path::new("missing.txt")).is_err());
+	}
+
+	#[test]
+	fn test_exclude_message() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		let feature = fixture.commit(author, 1704103200, "feat: add a feature", &[("a.txt", "1\n")]);
+		fixture.commit(author, 1704106800, "chore: bump version", &[("a.txt", "2\n")]);
+		fixture.commit(author, 1704110400, "Merge pull request #1", &[("a.txt", "3\n")]);
+		let fix = fixture.commit(author, 1704114000, "fix: not a chore: really", &[("a.txt", "4\n")]);
+		let repo = fixture.repo();
+
+		let args = CommitArgs::builder().exclude_message("^chore:").exclude_message("Merge pull request").build().unwrap();
+		assert!(args.to_string().contains("exclude_message:^chore:"));
+		let commits = repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		assert_eq!(vec![feature.to_string(), fix.to_string()], commits);
+
+		// the author filter is not inverted
+		let args = CommitArgs::builder().author(Author::new("Jane Doe")).exclude_message("^fix:").build().unwrap();
+		assert_eq!(3, repo.list_commits(args).unwrap().len());
+	}
+
+	#[test]
+	fn test_exclude_bots() {
+		init_log();
+		let fixture = Fixture::new();
+		let jane = fixture.commit("Jane Doe <jane@example.com>", 1704103200, "feature", &[("a.txt", "1\n")]);
+		fixture.commit("dependabot[bot] <49699333+dependabot[bot]@users.noreply.github.com>", 1704106800, "bump", &[]);
+		fixture.commit("renovate-bot <bot@renovateapp.com>", 1704110400, "update", &[]);
+		fixture.commit("CI Runner <ci@example.com>", 1704114000, "release", &[]);
+		// not a bot: the name doesn't end with `[bot]`
+		let robot = fixture.commit("Robot [bottom] Fan <fan@example.com>", 1704117600, "fix", &[("a.txt", "2\n")]);
+		let repo = fixture.repo();
+		let list = |args: CommitArgs| repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+		let args = CommitArgs::builder().exclude_bots(true).build().unwrap();
+		assert!(args.to_string().contains("exclude_bots:true"));
+		assert_eq!(3, list(args).len());
+
+		let args = CommitArgs::builder()
+			.exclude_bots(true)
+			.exclude_authors(vec!["CI Runner".to_string()])
+			.exclude_author("Nobody".to_string())
+			.build()
+			.unwrap();
+		let perl_args = args.clone().into_iter().filter(|arg| arg == "--perl-regexp").count();
+		assert_eq!(1, perl_args);
+		assert_eq!(vec![jane.to_string(), robot.to_string()], list(args));
+
+		assert!(CommitArgs::builder().author(Author::new("Jane Doe")).exclude_bots(true).build().is_err());
+	}
+
+	#[test]
+	fn test_since_until_precision() {
+		init_log();
+		let fixture = Fixture::new();
+		let author = "Jane Doe <jane@example.com>";
+		// 2024-01-01 08:00, 09:00 and 11:00
+		let early = fixture.commit(author, 1704096000, "early", &[("a.txt", "1\n")]);
+		let on_time = fixture.commit(author, 1704099600, "on time", &[("a.txt", "2\n")]);
+		let late = fixture.commit(author, 1704106800, "late", &[("a.txt", "3\n")]);
+		let repo = fixture.repo();
+		let list = |args: CommitArgs| repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+		// until 09:00: the commit two hours later, on the same day, is excluded
+		let args = CommitArgs::builder().until(1704099600).build().unwrap();
+		assert_eq!(vec![early.to_string(), on_time.to_string()], list(args));
+
+		let args = CommitArgs::builder().since(1704099600).build().unwrap();
+		assert_eq!(vec![on_time.to_string(), late.to_string()], list(args));
+
+		let args = CommitArgs::builder().since(1704099601).until(1704106799).build().unwrap();
+		assert!(list(args).is_empty());
+	}
+
+	#[test]
+	fn test_author_and_commit_date_range() {
+		init_log();
+		let fixture = Fixture::new();
+		let jane = "Jane Doe <jane@example.com>";
+		let john = "John Roe <john@example.com>";
+		// authored on 2024-01-01 08:00 and 2024-01-15, both rebased on 2024-02-01 at 00:00 and 01:00
+		let first = fixture.commit_with_dates(jane, 1704096000, 1706745600, "first", &[("a.txt", "1\n")]);
+		let second = fixture.commit_with_dates(jane, 1705276800, 1706749200, "second", &[("a.txt", "2\n")]);
+		// authored and committed on 2024-03-01
+		let third = fixture.commit_with_dates(john, 1709251200, 1709251200, "third", &[("b.txt", "3\n")]);
+		let repo = fixture.repo();
+		let list = |args: CommitArgs| repo.list_commits(args).unwrap().iter().map(|c| c.to_string()).collect::<Vec<_>>();
+		let (first, second, third) = (first.to_string(), second.to_string(), third.to_string());
+
+		// both bounds are inclusive, for both dates
+		let args = CommitArgs::builder().author_date_range(1704096000, 1705276800).build().unwrap();
+		assert_eq!(vec![first.clone(), second.clone()], list(args));
+		let args = CommitArgs::builder().author_date_range(1704096001, 1705276800).build().unwrap();
+		assert_eq!(vec![second.clone()], list(args));
+		let args = CommitArgs::builder().commit_date_range(1706745600, 1706749200).build().unwrap();
+		assert_eq!(vec![first.clone(), second.clone()], list(args));
+		let args = CommitArgs::builder().commit_date_range(1706745601, 1709251200).build().unwrap();
+		assert_eq!(vec![second.clone(), third.clone()], list(args));
+
+		// the rebased commits were committed in February, but written in January
+		let args = CommitArgs::builder().author_date_range(1706745600, 1709251200).build().unwrap();
+		assert_eq!(vec![third.clone()], list(args.clone()));
+		let commits = repo.list_commits_iter(args.clone()).unwrap().map(|c| c.unwrap().to_string()).collect::<Vec<_>>();
+		assert_eq!(vec![third.clone()], commits);
+		let details = repo.list_commit_details(args.clone()).unwrap();
+		assert_eq!(vec![third.clone()], details.iter().map(|c| c.hash.to_string()).collect::<Vec<_>>());
+		assert_eq!(vec![Author::try_from(john).unwrap()], repo.contributors(args.clone()).unwrap());
+		assert_eq!(vec![(Author::try_from(john).unwrap(), 1)], repo.commit_counts_per_author(args.clone()).unwrap());
+		assert_eq!("author_since:2024-02-01, author_until:2024-03-01", args.to_string());
+
+		// both ranges at once
+		let args = CommitArgs::builder()
+			.commit_date_range(1706745600, 1706749200)
+			.author_date_range(1705276800, 1709251200)
+			.build()
+			.unwrap();
+		assert_eq!(vec![second], list(args.clone()));
+		assert_eq!(
+			"commit_since:2024-02-01, commit_until:2024-02-01, author_since:2024-01-15, author_until:2024-03-01",
+			args.to_string()
+		);
+
+		assert!(CommitArgs::builder().author_date_range(1709251200, 1704096000).build().is_err());
+	}
+
+	#[test]
+	fn test_resolve_commit_hash() {
+		init_log();
+		let fixture = Fixture::new();
+		let commit = fixture.commit("Jane Doe <jane@example.com>", 1704103200, "first", &[("a.txt", "1\n")]);
+		let repo = fixture.repo();
+
+		let full = commit.to_string();
+		let short = CommitHash::try_from(&full[..7]).unwrap();
+		assert_eq!(&full[..7], short.short(10));
+		assert_eq!(full, repo.resolve(short.short(7)).unwrap().to_string());
+		assert_eq!(full, repo.resolve("main").unwrap().to_string());
+		assert_eq!(&full[..8], repo.resolve("HEAD").unwrap().short(8));
+		assert!(repo.resolve("missing").is_err());
+
+		assert!(CommitHash::try_from(full.as_str()).is_ok());
+		assert!(CommitHash::try_from(full.to_uppercase().as_str()).is_ok());
+		for invalid in ["", "abc", "main", "a9ae91ez", &format!("{full}0")] {
+			assert!(CommitHash::try_from(invalid).is_err(), "{invalid}");
+		}
+	}
+
+	#[test]
+	fn test_string_to_author() {
+		init_log();
+		let parse = |value: &str| -> (String, Option<String>) {
+			let author = Author::try_from(value).unwrap();
+			(author.name, author.email)
+		};
+		let author = |name: &str, email: Option<&str>| (name.to_string(), email.map(|e| e.to_string()));
+
+		assert_eq!(
+			author("Alessandro Crugnola", Some("alessandro@gmail.com")),
+			parse("Alessandro Crugnola <alessandro@gmail.com>")
+		);
+		assert_eq!(
+			author("Alessandro Crugnola <sephiroth>", Some("alessandro@gmail.com")),
+			parse("Alessandro Crugnola <sephiroth> <alessandro@gmail.com>")
+		);
+		assert_eq!(
+			author("Alessandro", Some("alessandro.crugnola_123+1@gmail.com")),
+			parse("Alessandro <alessandro.crugnola_123+1@gmail.com>")
+		);
+		assert_eq!(author("Alessandro Crugnola", None), parse("Alessandro Crugnola <>"));
+		assert_eq!(author("Alessandro Crugnola", None), parse("Alessandro Crugnola"));
+		assert_eq!(author("Crugnola, Alessandro", Some("a@b.com")), parse("\"Crugnola, Alessandro\" <a@b.com>"));
+		assert_eq!(author("Alessandro (sephiroth)", Some("a@b.com")), parse("Alessandro (sephiroth) <a@b.com>"));
+		assert_eq!(author("Alessandro", Some("a@b.com")), parse("  Alessandro   < a@b.com >  "));
+		assert_eq!(author("", Some("a@b.com")), parse("<a@b.com>"));
+
+		for value in ["", "  ", "Alessandro <a@b.com", "Alessandro a@b.com>", "Alessandro <a@b.com> x", "A <<a@b.com>>"] {
+			assert!(Author::try_from(value).is_err(), "{value}");
+		}
+
+		// the display format is parsed back
+		for value in [Author::new("Name <nick>").with_email("a@b.com"), Author::new("Name")] {
+			assert_eq!(value.identity_cmp(&Author::try_from(value.to_string()).unwrap()), std::cmp::Ordering::Equal);
+		}
+	}
+
+	/// A temporary git repository with commits created at fixed dates
+	struct Fixture {
+		dir: tempfile::TempDir,
+	}
+
+	impl Fixture {
+		fn new() -> Self {
+			let fixture = Fixture {
+				dir: tempfile::tempdir().unwrap(),
+			};
+			fixture.git(["init", "-q", "-b", "main"]);
+			fixture
+		}
+
+		fn path(&self) -> &Path {
+			self.dir.path()
+		}
+
+		fn repo(&self) -> Repo {
+			Repo::new(self.path())
+		}
+
+		fn git<I, S>(&self, args: I) -> String
+		where
+			I: IntoIterator<Item = S>,
+			S: AsRef<OsStr>,
+		{
+			self.git_with_env(args, [])
+		}
+
+		fn git_with_env<I, S, const N: usize>(&self, args: I, env: [(&str, String); N]) -> String
+		where
+			I: IntoIterator<Item = S>,
+			S: AsRef<OsStr>,
+		{
+			let output = Command::new("git")
+				.arg("-C")
+				.arg(self.path())
+				.args(args)
+				.env("GIT_CONFIG_NOSYSTEM", "1")
+				.env("GIT_COMMITTER_NAME", "Committer")
+				.env("GIT_COMMITTER_EMAIL", "committer@example.com")
+				.envs(env)
+				.output()
+				.unwrap();
+			assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+			String::from_utf8(output.stdout).unwrap()
+		}
+
+		/// Write the given files and commit them, returning the new commit hash
+		fn commit(&self, author: &str, timestamp: i64, message: &str, files: &[(&str, &str)]) -> CommitHash {
+			self.commit_with_dates(author, timestamp, timestamp, message, files)
+		}
+
+		fn commit_with_dates(
+			&self,
+			author: &str,
+			author_timestamp: i64,
+			committer_timestamp: i64,
+			message: &str,
+			files: &[(&str, &str)],
+		) -> CommitHash {
+			for (name, content) in files {
+				let path = self.path().join(name);
+				std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+				std::fs::write(path, content).unwrap();
+			}
+
+			let author = Author::try_from(author).unwrap();
+			self.git(["add", "-A"]);
+			self.git_with_env(
+				["commit", "-q", "--allow-empty", "-m", message],
+				[
+					("GIT_AUTHOR_NAME", author.name),
+					("GIT_AUTHOR_EMAIL", author.email.unwrap_or_default()),
+					("GIT_AUTHOR_DATE", format!("@{author_timestamp} +0000")),
+					("GIT_COMMITTER_DATE", format!("@{committer_timestamp} +0000")),
+				],
+			);
+			CommitHash(self.git(["rev-parse", "HEAD"]).trim().to_string())
+		}
+	}
+
+	#[derive(Debug)]
+	struct Ticker {
+		start: Instant,
+		current: Instant,
+	}
+
+	impl Ticker {
+		pub fn new() -> Self {
+			Ticker {
+				start: Instant::now(),
+				current: Instant::now(),
+			}
+		}
+
+		pub fn tick(&mut self) -> (Duration, Duration) {
+			let elapsed = self.current.elapsed();
+			let total = self.start.elapsed();
+			self.current = Instant::now();
+			(elapsed, total)
+		}
+	}
+}
+
+/// Tests for the stats aggregations. These don't need git and run without the `repo` feature
+#[cfg(test)]
+mod aggregations {
+	use std::collections::HashMap;
+	use std::time::Duration;
+
+	use chrono::{FixedOffset, Weekday};
+	use itertools::Itertools;
+
+	use crate::traits::{CommitStatsExt, Merge};
+	use crate::{
+		Author, Churn, CoAuthorMode, CommitDetail, CommitHash, CommitStats, CommitsHeatMap, CommitsPerAuthor,
+		CommitsPerMonth, CommitsPerWeek, CommitsPerWeekday, FileStat, GlobalStat, MinimalCommitDetail, Shares,
+		SimpleStat, SizeBucket, SortOrder, SortStatsBy, StreakInfo, WeekStart, Weekend, DEFAULT_SIZE_THRESHOLDS,
+	};
+
+	#[test]
+	fn test_stable_author_representative() {
+		let authors = [
+			Author::new("john doe").with_email("John.Doe@example.com"),
+			Author::new("John Doe").with_email("john.doe@example.com"),
+			Author::new("John Doe").with_email("jdoe@example.com"),
+		];
+
+		let mut representatives = vec![];
+		for offset in 0..authors.len() {
+			let commits = (0..authors.len())
+				.map(|index| {
+					let author = authors[(index + offset) % authors.len()].clone();
+					commit_detail(author, index as i64 * 3600, CommitStats::default())
+				})
+				.collect::<Vec<_>>();
+
+			let commits_per_author = commits.commits_per_author();
+			assert_eq!(1, commits_per_author.detailed_stats().len());
+
+			let global_stats = commits_per_author.global_stats(SortStatsBy::Commits);
+			assert_eq!(3, global_stats[0].commits_count);
+			representatives.push(global_stats[0].author.to_string());
+			assert_eq!("jdoe@example.com", global_stats[0].email());
+		}
+
+		assert!(representatives.iter().all(|r| r == "John Doe <jdoe@example.com>"));
+	}
+
+	#[test]
+	fn test_commits_per_author_large_history() {
+		const AUTHORS: usize = 500;
+		const COMMITS: usize = 50_000;
+
+		// every author commits with its name and email, with its name only (in uppercase) and with an alias sharing
+		// the email: they all match the first variant
+		let commits = (0..COMMITS)
+			.map(|index| {
+				let id = index % AUTHORS;
+				let author = match (index / AUTHORS) % 3 {
+					0 => Author::new(format!("Author {id}")).with_email(&format!("author{id}@example.com")),
+					1 => Author::new(format!("AUTHOR {id}")),
+					_ => Author::new(format!("alias {id}")).with_email(&format!("AUTHOR{id}@example.com")),
+				};
+				commit_detail(author, index as i64 * 60, CommitStats::default())
+			})
+			.collect::<Vec<_>>();
+
+		let commits_per_author = commits.commits_per_author();
+		let global_stats = commits_per_author.global_stats(SortStatsBy::Name);
+		assert_eq!(AUTHORS, global_stats.len());
+		assert!(global_stats.iter().all(|stat| stat.commits_count == COMMITS / AUTHORS));
+		assert!(global_stats.iter().all(|stat| stat.author.name.starts_with("AUTHOR ") && stat.author.email.is_none()));
+
+		let grouped = commits_per_author.detailed_stats();
+		let first = &grouped[&Author::new("AUTHOR 0")];
+		assert!(first.windows(2).all(|pair| pair[0].author_timestamp < pair[1].author_timestamp));
+	}
+
+	#[test]
+	fn test_global_stats_with_shares() {
+		let stats = |lines_added, lines_deleted| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted,
+		};
+		let commits = vec![
+			commit_detail(Author::new("one"), 0, stats(30, 0)),
+			commit_detail(Author::new("one"), 3600, stats(30, 0)),
+			commit_detail(Author::new("one"), 7200, stats(15, 0)),
+			commit_detail(Author::new("two"), 10800, stats(25, 0)),
+		];
+		let shares = commits.commits_per_author().global_stats_with_shares(SortStatsBy::Commits);
+		assert_eq!(2, shares.len());
+		assert_eq!("one", shares[0].0.author.name);
+		assert_eq!(
+			Shares {
+				commits_pct: 75.0,
+				lines_added_pct: 75.0,
+				lines_deleted_pct: 0.0,
+			},
+			shares[0].1
+		);
+		assert_eq!(25.0, shares[1].1.commits_pct);
+		assert_eq!(25.0, shares[1].1.lines_added_pct);
+
+		// no NaN without lines, nor without commits
+		let empty_commits = vec![commit_detail(Author::new("one"), 0, CommitStats::default())];
+		let shares = empty_commits.commits_per_author().global_stats_with_shares(SortStatsBy::Commits);
+		assert_eq!(100.0, shares[0].1.commits_pct);
+		assert_eq!(0.0, shares[0].1.lines_added_pct);
+		let no_commits: Vec<CommitDetail> = vec![];
+		assert!(no_commits.commits_per_author().global_stats_with_shares(SortStatsBy::Commits).is_empty());
+	}
+
+	#[test]
+	fn test_sort_global_stats_by_name_and_email() {
+		let commits = vec![
+			commit_detail(Author::new("carol").with_email("a@example.com"), 0, CommitStats::default()),
+			commit_detail(Author::new("Bob").with_email("C@example.com"), 3600, CommitStats::default()),
+			commit_detail(Author::new("alice").with_email("b@example.com"), 7200, CommitStats::default()),
+		];
+		let commits_per_author = commits.commits_per_author();
+
+		let names = commits_per_author
+			.global_stats(SortStatsBy::Name)
+			.into_iter()
+			.map(|stat| stat.author.name)
+			.collect::<Vec<_>>();
+		assert_eq!(vec!["alice", "Bob", "carol"], names);
+
+		let emails = commits_per_author
+			.global_stats(SortStatsBy::Email)
+			.iter()
+			.map(|stat| stat.email().to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(vec!["a@example.com", "b@example.com", "C@example.com"], emails);
+	}
+
+	#[test]
+	fn test_global_stats_sort_order() {
+		let stats = |lines_added| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted: 0,
+		};
+		let commits = vec![
+			commit_detail(Author::new("one"), 0, stats(10)),
+			commit_detail(Author::new("two"), 3600, stats(4)),
+			commit_detail(Author::new("two"), 7200, stats(4)),
+			commit_detail(Author::new("three"), 10800, stats(1)),
+			commit_detail(Author::new("three"), 14400, stats(1)),
+			commit_detail(Author::new("three"), 18000, stats(1)),
+		];
+		let commits_per_author = commits.commits_per_author();
+		let names = |stats: Vec<GlobalStat>| stats.into_iter().map(|s| s.author.name).collect::<Vec<_>>();
+
+		assert_eq!(
+			vec!["three", "two", "one"],
+			names(commits_per_author.global_stats_ordered(SortStatsBy::Commits, SortOrder::Descending))
+		);
+		assert_eq!(
+			vec!["one", "two", "three"],
+			names(commits_per_author.global_stats_ordered(SortStatsBy::Commits, SortOrder::Ascending))
+		);
+		assert_eq!(
+			vec!["one", "two", "three"],
+			names(commits_per_author.global_stats_ordered(SortStatsBy::LinesAdded, SortOrder::Descending))
+		);
+		assert_eq!(
+			vec!["three", "two", "one"],
+			names(commits_per_author.global_stats_ordered(SortStatsBy::LinesAdded, SortOrder::Ascending))
+		);
+		assert_eq!(
+			names(commits_per_author.global_stats(SortStatsBy::Commits)),
+			names(commits_per_author.global_stats_ordered(SortStatsBy::Commits, SortOrder::Descending))
+		);
+	}
+
+	#[test]
+	fn test_aggregations_without_git() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 2,
+			lines_deleted: 1,
+		};
+		// 2024-01-01 (Mon) 10:00, 2024-01-02 (Tue) 15:00, 2024-02-03 (Sat) 10:00
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, stats),
+			commit_detail(Author::new("two"), 1704207600, stats),
+			commit_detail(Author::new("one"), 1706954400, stats),
+		];
+
+		let commits_per_author = commits.commits_per_author();
+		assert_eq!(2, commits_per_author.detailed_stats().len());
+		let global_stats = commits_per_author.global_stats(SortStatsBy::Commits);
+		assert_eq!("one", global_stats[0].author.name);
+		assert_eq!(4, global_stats[0].stats.lines_added);
+
+		let global_stats = commits.clone().commits_per_month().global_stats();
+		assert_eq!(2, global_stats.get("2024-01").unwrap().commits_count);
+		assert_eq!(1, global_stats.get("2024-02").unwrap().commits_count);
+
+		let global_stats = commits.clone().commits_per_weekday().global_stats();
+		assert_eq!(1, global_stats.get(&0).unwrap().commits_count);
+		assert_eq!(1, global_stats.get(&1).unwrap().commits_count);
+		assert_eq!(1, global_stats.get(&5).unwrap().commits_count);
+
+		let global_stats = commits.clone().commits_per_day_hour().global_stats();
+		assert_eq!(2, global_stats.get(&10).unwrap().commits_count);
+		assert_eq!(1, global_stats.get(&15).unwrap().commits_count);
+
+		let global_stats = commits.commits_heatmap().global_stats();
+		assert_eq!(1, global_stats[0][10].commits_count);
+		assert_eq!(1, global_stats[1][15].commits_count);
+		assert_eq!(1, global_stats[5][10].commits_count);
+	}
+
+	#[test]
+	fn test_cohort_retention() {
+		let stats = CommitStats::default();
+		// 2024-01-10, 2024-02-10, 2024-03-10
+		let (jan, feb, mar) = (1704880800, 1707559200, 1710064800);
+		let commits = vec![
+			commit_detail(Author::new("a"), jan, stats),
+			commit_detail(Author::new("b"), jan + 3600, stats),
+			commit_detail(Author::new("a"), feb, stats),
+			commit_detail(Author::new("c"), feb + 3600, stats),
+			commit_detail(Author::new("b"), mar, stats),
+			commit_detail(Author::new("c"), mar + 3600, stats),
+		];
+
+		let retention = commits.commits_per_month().cohort_retention();
+		assert_eq!(2, retention.len());
+		assert_eq!(&vec![2, 1, 1], retention.get("2024-01").unwrap());
+		assert_eq!(&vec![1, 1], retention.get("2024-02").unwrap());
+	}
+
+	#[test]
+	fn test_commits_per_author_with_co_authors() {
+		let stats = CommitStats {
+			files_changed: 3,
+			lines_added: 10,
+			lines_deleted: 5,
+		};
+		let mut pair_commit = commit_detail(Author::new("one"), 0, stats);
+		pair_commit.co_authors = vec![Author::new("two"), Author::new("three")];
+		let commits = vec![pair_commit, commit_detail(Author::new("two"), 3600, stats)];
+
+		let find = |stats: &[GlobalStat], name: &str| stats.iter().find(|s| s.author.name == name).cloned();
+
+		let ignored = commits.commits_per_author_with(CoAuthorMode::Ignore).global_stats(SortStatsBy::Name);
+		assert_eq!(2, ignored.len());
+		assert!(find(&ignored, "three").is_none());
+
+		let full = commits.commits_per_author_with(CoAuthorMode::Full).global_stats(SortStatsBy::Name);
+		assert_eq!(3, full.len());
+		assert_eq!(2, find(&full, "two").unwrap().commits_count);
+		assert_eq!(20, find(&full, "two").unwrap().stats.lines_added);
+		assert_eq!(10, find(&full, "three").unwrap().stats.lines_added);
+
+		let split = commits.commits_per_author_with(CoAuthorMode::Split).global_stats(SortStatsBy::Name);
+		assert_eq!(3, split.len());
+		assert_eq!(4, find(&split, "one").unwrap().stats.lines_added);
+		assert_eq!(1, find(&split, "one").unwrap().stats.files_changed);
+		assert_eq!(13, find(&split, "two").unwrap().stats.lines_added);
+		assert_eq!(3, find(&split, "three").unwrap().stats.lines_added);
+		let total_added: u64 = split.iter().map(|s| s.stats.lines_added).sum();
+		assert_eq!(20, total_added);
+	}
+
+	#[test]
+	fn test_stats_per_extension() {
+		let mut first = commit_detail(Author::new("one"), 0, CommitStats::default());
+		first.files = vec![file_stat("src/lib.rs", 10, 2), file_stat("src/main.rs", 5, 0), file_stat("README.md", 3, 1)];
+		let mut second = commit_detail(Author::new("two"), 3600, CommitStats::default());
+		second.files = vec![file_stat("src/lib.rs", 1, 1), file_stat("Makefile", 2, 0)];
+
+		let stats = vec![first, second].stats_per_extension();
+		let stats = stats.stats();
+		assert_eq!(3, stats.len());
+
+		let rs = stats.get("rs").unwrap();
+		assert_eq!(2, rs.commits_count);
+		assert_eq!(3, rs.stats.files_changed);
+		assert_eq!(16, rs.stats.lines_added);
+		assert_eq!(3, rs.stats.lines_deleted);
+
+		let md = stats.get("md").unwrap();
+		assert_eq!(1, md.commits_count);
+		assert_eq!(3, md.stats.lines_added);
+
+		let none = stats.get("").unwrap();
+		assert_eq!(1, none.commits_count);
+		assert_eq!(2, none.stats.lines_added);
+	}
+
+	#[test]
+	fn test_merge() {
+		let stats = |lines: u64| CommitStats {
+			files_changed: 1,
+			lines_added: lines,
+			lines_deleted: 0,
+		};
+		let with_file = |mut commit: CommitDetail, path: &str| {
+			commit.files = vec![file_stat(path, commit.stats.lines_added, 0)];
+			commit
+		};
+		// 2024-01-01 (Mon) 08:00, 2024-01-03 (Wed) 10:00, 2024-02-05 (Mon) 08:00
+		let first_repo = vec![
+			with_file(commit_detail(Author::new("alice").with_email("a@example.com"), 1704096000, stats(1)), "a.rs"),
+			with_file(commit_detail(Author::new("bob").with_email("b@example.com"), 1704276000, stats(2)), "b.md"),
+		];
+		let second_repo = vec![
+			with_file(commit_detail(Author::new("Alice").with_email("A@example.com"), 1704096000, stats(4)), "c.rs"),
+			with_file(commit_detail(Author::new("carol").with_email("c@example.com"), 1707120000, stats(8)), "d"),
+		];
+		let all = first_repo.iter().chain(second_repo.iter()).cloned().collect::<Vec<_>>();
+
+		let merged = first_repo.commits_per_author().merge(second_repo.commits_per_author());
+		let global = merged.global_stats(SortStatsBy::Name);
+		assert_eq!(
+			vec![("Alice".to_string(), 2, 5), ("bob".to_string(), 1, 2), ("carol".to_string(), 1, 8)],
+			global.iter().map(|g| (g.author.name.clone(), g.commits_count, g.stats.lines_added)).collect::<Vec<_>>()
+		);
+		// the key doesn't depend on the merge order
+		let reversed = second_repo.commits_per_author().merge(first_repo.commits_per_author());
+		assert_eq!("Alice", reversed.global_stats(SortStatsBy::Name)[0].author.name);
+		assert!(CommitsPerAuthor::merge_all(Vec::new()).detailed_stats().is_empty());
+		let merged = CommitsPerAuthor::merge_all([&first_repo, &second_repo].map(|c| c.commits_per_author()));
+		assert_eq!(3, merged.detailed_stats().len());
+
+		let merged = first_repo.clone().commits_heatmap().merge(second_repo.clone().commits_heatmap());
+		assert_eq!(all.clone().commits_heatmap().global_stats(), merged.global_stats());
+		// merged under the smallest identity
+		let alice = &merged.detailed_stats()[&Author::new("Alice").with_email("A@example.com")];
+		assert_eq!(2, alice[0][8].commits_count);
+		assert_eq!(5, alice[0][8].stats.lines_added);
+
+		let merged = first_repo.clone().commits_per_weekday().merge(second_repo.clone().commits_per_weekday());
+		assert_eq!(all.clone().commits_per_weekday().global_stats(), merged.global_stats());
+		let merged = first_repo.clone().commits_per_day_hour().merge(second_repo.clone().commits_per_day_hour());
+		assert_eq!(all.clone().commits_per_day_hour().global_stats(), merged.global_stats());
+		let repos = [first_repo.clone(), second_repo.clone()];
+		let merged = CommitsPerMonth::merge_all(repos.map(|c| c.commits_per_month()));
+		assert_eq!(all.clone().commits_per_month().global_stats(), merged.global_stats());
+		let merged = first_repo.clone().stats_per_extension().merge(second_repo.clone().stats_per_extension());
+		assert_eq!(all.stats_per_extension().stats(), merged.stats());
+
+		// the months between two disjoint ranges are filled, as on the concatenated commits
+		let winter = vec![
+			commit_detail(Author::new("alice"), 1704844800, stats(1)),
+			commit_detail(Author::new("alice"), 1707523200, stats(1)),
+		];
+		let summer = vec![
+			commit_detail(Author::new("bob"), 1715299200, stats(1)),
+			commit_detail(Author::new("bob"), 1717977600, stats(1)),
+		];
+		let all = winter.iter().chain(summer.iter()).cloned().collect::<Vec<_>>();
+		let merged = winter.clone().commits_per_month().merge(summer.clone().commits_per_month());
+		assert_eq!(all.clone().commits_per_month().global_stats(), merged.global_stats());
+		assert_eq!(6, merged.detailed_stats().len());
+		assert!(merged.detailed_stats()["2024-03"].is_empty());
+
+		let merged = winter.commits_per_week().merge(summer.commits_per_week());
+		assert_eq!(all.commits_per_week().global_stats(), merged.global_stats());
+		assert!(merged.detailed_stats()["2024-W12"].is_empty());
+	}
+
+	#[test]
+	fn test_buckets_with_offset() {
+		// Tuesday 2024-01-02 01:30 UTC, and Thursday 2024-02-01 02:00 UTC
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704159000, CommitStats::default()),
+			commit_detail(Author::new("one"), 1706752800, CommitStats::default()),
+		];
+		let utc = FixedOffset::east_opt(0).unwrap();
+		let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+
+		// the default stays UTC
+		assert_eq!(
+			commits.clone().commits_per_weekday().global_stats(),
+			commits.clone().commits_per_weekday_in(utc).global_stats()
+		);
+		assert_eq!(1, commits.clone().commits_per_weekday().global_stats()[&1].commits_count);
+
+		// at UTC-05:00 the commits are on the evening of the previous day
+		let weekdays = commits.clone().commits_per_weekday_in(new_york).global_stats();
+		assert_eq!(1, weekdays[&0].commits_count);
+		assert_eq!(0, weekdays[&1].commits_count);
+		assert_eq!(1, weekdays[&2].commits_count);
+
+		let hours = commits.clone().commits_per_day_hour_in(new_york).global_stats();
+		assert_eq!(1, hours[&20].commits_count);
+		assert_eq!(1, hours[&21].commits_count);
+		assert_eq!(0, hours[&1].commits_count);
+
+		let heatmap = commits.clone().commits_heatmap_in(new_york).global_stats();
+		assert_eq!(1, heatmap[0][20].commits_count);
+		assert_eq!(1, heatmap[2][21].commits_count);
+
+		// the February commit moves to January, so there is a single month
+		assert_eq!(
+			vec!["2024-01", "2024-02"],
+			commits.clone().commits_per_month().global_stats().into_keys().sorted().collect::<Vec<_>>()
+		);
+		let months = commits.clone().commits_per_month_in(new_york).global_stats();
+		assert_eq!(1, months.len());
+		assert_eq!(2, months["2024-01"].commits_count);
+
+		// Monday 2024-01-01 is still in the first ISO week, Wednesday 2024-01-31 in the fifth
+		let weeks = commits.commits_per_week_in(new_york).global_stats();
+		assert_eq!(1, weeks["2024-W01"].commits_count);
+		assert_eq!(1, weeks["2024-W05"].commits_count);
+	}
+
+	#[test]
+	fn test_commits_per_week() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 2,
+			lines_deleted: 1,
+		};
+		let alice = Author::new("Alice");
+		let bob = Author::new("Bob");
+		// 2024-12-20 (Fri) 12:00, 2024-12-30 (Mon) 09:00, 2025-01-08 (Wed) 18:00
+		let commits = vec![
+			commit_detail(alice.clone(), 1734696000, stats),
+			commit_detail(bob.clone(), 1735549200, stats),
+			commit_detail(alice.clone(), 1735549200 + 3600, stats),
+			commit_detail(bob.clone(), 1735549200 + 7200, stats),
+			commit_detail(alice.clone(), 1736359200, stats),
+		];
+
+		let per_week = commits.commits_per_week();
+		let weeks = per_week.sorted_detailed_stats();
+		// the last days of 2024 are in the first ISO week of 2025
+		assert_eq!(
+			vec!["2024-W51", "2024-W52", "2025-W01", "2025-W02"],
+			weeks.iter().map(|(week, _)| week.as_str()).collect::<Vec<_>>()
+		);
+		assert!(weeks[1].1.is_empty());
+		assert_eq!(1, per_week.detailed_stats()["2025-W01"][&alice].commits_count);
+		assert_eq!(2, per_week.detailed_stats()["2025-W01"][&bob].commits_count);
+		let global = per_week.global_stats();
+		assert_eq!(3, global["2025-W01"].commits_count);
+		assert_eq!(6, global["2025-W01"].stats.lines_added);
+		assert_eq!(SimpleStat::default(), global["2024-W52"]);
+
+		// 2021-01-01 is in the last week of 2020
+		let mut accumulated = CommitsPerWeek::default();
+		accumulated.accumulate(&commit_detail(bob, 1609495200, stats));
+		assert_eq!(vec!["2020-W53"], accumulated.detailed_stats().keys().collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_size_distribution() {
+		let stats = |lines_added: u64, lines_deleted: u64| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted,
+		};
+		let alice = Author::new("Alice");
+		let bob = Author::new("Bob");
+		let commits = vec![
+			commit_detail(alice.clone(), 0, stats(0, 0)),
+			commit_detail(alice.clone(), 1, stats(5, 4)),
+			commit_detail(bob.clone(), 2, stats(5, 5)),
+			commit_detail(alice.clone(), 3, stats(150, 49)),
+			commit_detail(bob.clone(), 4, stats(1000, 0)),
+			commit_detail(bob.clone(), 5, stats(u64::MAX, 1)),
+		];
+
+		let histogram = commits.size_distribution(&DEFAULT_SIZE_THRESHOLDS);
+		assert_eq!(
+			vec!["0", "1-9", "10-49", "50-199", "200-999", "1000+"],
+			histogram.buckets.iter().map(|bucket| bucket.to_string()).collect::<Vec<_>>()
+		);
+		assert_eq!(vec![1, 1, 1, 1, 0, 2], histogram.buckets.iter().map(|b| b.commits_count).collect::<Vec<_>>());
+		assert_eq!(vec![1, 1, 0, 1, 0, 0], histogram.per_author[&alice]);
+		assert_eq!(vec![0, 0, 1, 0, 0, 2], histogram.per_author[&bob]);
+
+		// unsorted thresholds, with duplicates and 0
+		let histogram = commits.size_distribution(&[100, 0, 10, 100]);
+		assert_eq!(
+			vec![
+				SizeBucket {
+					min: 0,
+					max: Some(9),
+					commits_count: 2,
+				},
+				SizeBucket {
+					min: 10,
+					max: Some(99),
+					commits_count: 1,
+				},
+				SizeBucket {
+					min: 100,
+					max: None,
+					commits_count: 3,
+				},
+			],
+			histogram.buckets
+		);
+
+		let histogram = commits.size_distribution(&[]);
+		assert_eq!("0+", histogram.buckets[0].to_string());
+		assert_eq!(6, histogram.buckets[0].commits_count);
+		assert!(Vec::<CommitDetail>::new().size_distribution(&DEFAULT_SIZE_THRESHOLDS).per_author.is_empty());
+	}
+
+	#[test]
+	fn test_sorted_detailed_stats() {
+		// 2024-01-03 (Wed) 10:00, 2024-01-01 (Mon) 08:00, 2023-12-31 (Sun) 23:00
+		let stats = CommitStats::default();
+		let mut first = commit_detail(Author::new("bob").with_email("bob@example.com"), 1704276000, stats);
+		first.files = vec![file_stat("src/lib.rs", 1, 0), file_stat("Makefile", 1, 0)];
+		let mut second = commit_detail(Author::new("carol").with_email("c@example.com"), 1704096000, stats);
+		second.files = vec![file_stat("README.md", 1, 0)];
+		let third = commit_detail(Author::new("Alice").with_email("a@example.com"), 1704063600, stats);
+		let commits = vec![first, second, third];
+
+		let names = |authors: Vec<&Author>| authors.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+		let expected = vec!["Alice <a@example.com>", "bob <bob@example.com>", "carol <c@example.com>"];
+		let per_author = commits.commits_per_author();
+		assert_eq!(expected, names(per_author.sorted_detailed_stats().into_iter().map(|(a, _)| a).collect()));
+		let heatmap = commits.clone().commits_heatmap();
+		assert_eq!(expected, names(heatmap.sorted_detailed_stats().into_iter().map(|(a, _)| a).collect()));
+
+		let per_weekday = commits.clone().commits_per_weekday();
+		let per_weekday = per_weekday.sorted_detailed_stats();
+		assert_eq!((0..7).collect::<Vec<u8>>(), per_weekday.iter().map(|(weekday, _)| **weekday).collect::<Vec<_>>());
+		assert_eq!(vec!["carol <c@example.com>"], names(per_weekday[0].1.iter().map(|(author, _)| *author).collect()));
+		assert!(per_weekday[1].1.is_empty());
+		let per_month = commits.clone().commits_per_month();
+		let per_month = per_month.sorted_detailed_stats();
+		assert_eq!(vec!["2023-12", "2024-01"], per_month.iter().map(|(month, _)| month.as_str()).collect::<Vec<_>>());
+		assert_eq!(
+			vec!["bob <bob@example.com>", "carol <c@example.com>"],
+			names(per_month[1].1.iter().map(|(author, _)| *author).collect())
+		);
+		let per_hour = commits.clone().commits_per_day_hour();
+		let per_hour = per_hour.sorted_detailed_stats();
+		assert_eq!((0..24).collect::<Vec<u32>>(), per_hour.iter().map(|(hour, _)| **hour).collect::<Vec<_>>());
+
+		let extensions = commits.stats_per_extension();
+		let extensions = extensions.sorted_stats().into_iter().map(|(ext, _)| ext.as_str()).collect::<Vec<_>>();
+		assert_eq!(vec!["", "md", "rs"], extensions);
+	}
+
+	#[test]
+	fn test_stats_per_directory() {
+		let mut first = commit_detail(Author::new("one"), 0, CommitStats::default());
+		first.files = vec![
+			file_stat("src/lib.rs", 10, 2),
+			file_stat("src/repo/git.rs", 5, 0),
+			file_stat("README.md", 3, 1),
+		];
+		let mut second = commit_detail(Author::new("two"), 3600, CommitStats::default());
+		second.files = vec![file_stat("src/repo/git.rs", 1, 1), file_stat("docs/guide/intro.md", 2, 0)];
+		let commits = vec![first, second];
+
+		let stats = commits.clone().stats_per_directory(1);
+		assert_eq!(3, stats.len());
+		assert_eq!(2, stats.get("src").unwrap().commits_count);
+		assert_eq!(3, stats.get("src").unwrap().stats.files_changed);
+		assert_eq!(16, stats.get("src").unwrap().stats.lines_added);
+		assert_eq!(1, stats.get("docs").unwrap().commits_count);
+		assert_eq!(1, stats.get("README.md").unwrap().commits_count);
+
+		let stats = commits.stats_per_directory(2);
+		assert_eq!(4, stats.len());
+		assert_eq!(2, stats.get("src/repo").unwrap().commits_count);
+		assert_eq!(10, stats.get("src/lib.rs").unwrap().stats.lines_added);
+		assert_eq!(2, stats.get("docs/guide").unwrap().stats.lines_added);
+		assert_eq!(3, stats.get("README.md").unwrap().stats.lines_added);
+	}
+
+	fn file_stat(path: &str, lines_added: u64, lines_deleted: u64) -> FileStat {
+		FileStat {
+			path: path.to_string(),
+			lines_added,
+			lines_deleted,
+		}
+	}
+
+	#[test]
+	fn test_landing_delay_stats() {
+		const DAY: i64 = 86400;
+		let commits = [0, 1, 2, 10, -1]
+			.iter()
+			.enumerate()
+			.map(|(index, delay)| {
+				let mut commit = commit_detail(Author::new("one"), index as i64 * DAY, CommitStats::default());
+				commit.committer_timestamp = commit.author_timestamp + delay * DAY;
+				commit
+			})
+			.collect::<Vec<_>>();
+
+		let stats = commits.landing_delay_stats();
+		assert_eq!(4, stats.commits_count);
+		assert_eq!(1, stats.negative_count);
+		assert_eq!(3.25, stats.average_days);
+		assert_eq!(1.5, stats.median_days);
+		assert_eq!(10.0, stats.max_days);
+
+		let stats = Vec::<CommitDetail>::new().landing_delay_stats();
+		assert_eq!(0, stats.commits_count);
+		assert_eq!(0.0, stats.average_days);
+	}
+
+	#[test]
+	fn test_global_stat_shares() {
+		let stats = |files_changed, lines_added, lines_deleted| CommitStats {
+			files_changed,
+			lines_added,
+			lines_deleted,
+		};
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, stats(1, 10, 2)),
+			commit_detail(Author::new("two"), 1704207600, stats(2, 5, 5)),
+			commit_detail(Author::new("one"), 1706954400, stats(1, 0, 0)),
+			commit_detail(Author::new("three"), 1706958000, stats(1, 0, 8)),
+		];
+		let total = commits.iter().cloned().map(SimpleStat::from).fold(SimpleStat::new(), |acc, item| acc + item);
+		let global_stats = commits.commits_per_author().global_stats(SortStatsBy::Commits);
+
+		assert_eq!(0.5, global_stats[0].share_of(&total));
+		assert_eq!(0.4, global_stats[0].churn_share_of(&total));
+		let shares: f64 = global_stats.iter().map(|stat| stat.share_of(&total)).sum();
+		let churn_shares: f64 = global_stats.iter().map(|stat| stat.churn_share_of(&total)).sum();
+		assert!((shares - 1.0).abs() < 1e-9);
+		assert!((churn_shares - 1.0).abs() < 1e-9);
+
+		assert_eq!(0.0, global_stats[0].share_of(&SimpleStat::new()));
+		assert_eq!(0.0, global_stats[0].churn_share_of(&SimpleStat::new()));
+	}
+
+	#[test]
+	fn test_ordered_serialization() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 1,
+			lines_deleted: 0,
+		};
+		let paths = ["src/lib.rs", "README.md", "Cargo.toml", "Makefile", "build.sh", "main.c", "a.py", "b.kt"];
+		let commits = paths
+			.iter()
+			.enumerate()
+			.map(|(index, path)| {
+				let mut commit = commit_detail(Author::new("one"), 1704103200 + index as i64, stats);
+				commit.files = vec![file_stat(path, 1, 0)];
+				commit
+			})
+			.collect::<Vec<_>>();
+		let mut reversed = commits.clone();
+		reversed.reverse();
+
+		let json = serde_json::to_string(&commits.stats_per_extension()).unwrap();
+		assert_eq!(json, serde_json::to_string(&reversed.clone().stats_per_extension()).unwrap());
+		assert_eq!(json, serde_json::to_string(&reversed.stats_per_extension()).unwrap());
+
+		let positions = ["", "c", "kt", "md", "py", "rs", "sh", "toml"]
+			.iter()
+			.map(|key| json.find(&format!("\"{key}\":")).unwrap())
+			.collect::<Vec<_>>();
+		assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{json}");
+	}
+
+	#[test]
+	fn test_test_coverage_commits() {
+		let stats = CommitStats::default();
+		let commit = |timestamp: i64, paths: &[&str]| {
+			let mut commit = commit_detail(Author::new("one"), timestamp, stats);
+			commit.files = paths.iter().map(|path| file_stat(path, 1, 0)).collect();
+			commit
+		};
+		let commits = vec![
+			commit(1704103200, &["tests/integration.rs"]),
+			commit(1704106800, &["src/lib.rs", "src/impls.rs"]),
+			commit(1704110400, &["src/lib.rs", "tests/integration.rs"]),
+			commit(1704114000, &["src/repo.rs", "src/repo_test.rs"]),
+			commit(1704117600, &["src/lib.rs"]),
+			commit(1704121200, &[]),
+		];
+
+		assert_eq!((1, 2, 2), commits.test_coverage_commits(&["tests/**", "**/*_test.rs"]));
+		assert_eq!((0, 5, 0), commits.test_coverage_commits(&[]));
+		assert_eq!((0, 5, 0), commits.test_coverage_commits(&["[invalid"]));
+	}
+
+	#[test]
+	fn test_commits_per_month_day_overflow() {
+		let stats = CommitStats::default();
+		// 2024-02-10 and 2024-03-31: the 31st doesn't exist in February
+		let commits = vec![
+			commit_detail(Author::new("one"), 1707562800, stats),
+			commit_detail(Author::new("one"), 1711882800, stats),
+		];
+		let global_stats = commits.commits_per_month().global_stats();
+		assert_eq!(2, global_stats.len());
+		assert_eq!(1, global_stats.get("2024-02").unwrap().commits_count);
+		assert_eq!(1, global_stats.get("2024-03").unwrap().commits_count);
+
+		// 2023-11-30 and 2024-01-31, across the year boundary
+		let commits = vec![
+			commit_detail(Author::new("one"), 1701342000, stats),
+			commit_detail(Author::new("one"), 1706698800, stats),
+		];
+		let global_stats = commits.commits_per_month().global_stats();
+		assert_eq!(3, global_stats.len());
+		assert_eq!(1, global_stats.get("2023-11").unwrap().commits_count);
+		assert_eq!(0, global_stats.get("2023-12").unwrap().commits_count);
+		assert_eq!(1, global_stats.get("2024-01").unwrap().commits_count);
+	}
+
+	#[test]
+	fn test_anonymize() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 1,
+			lines_deleted: 0,
+		};
+		let commits = vec![
+			commit_detail(Author::new("two").with_email("two@example.com"), 1704103200, stats),
+			commit_detail(Author::new("one").with_email("one@example.com"), 1704106800, stats),
+			commit_detail(Author::new("one").with_email("one@example.com"), 1704110400, stats),
+			commit_detail(Author::new("three").with_email("three@example.com"), 1704114000, stats),
+		];
+		let mut reversed = commits.clone();
+		reversed.reverse();
+
+		let (anonymized, mapping) = commits.commits_per_author().anonymize();
+		let (_, other_mapping) = reversed.commits_per_author().anonymize();
+		assert_eq!(mapping, other_mapping);
+		assert_eq!("one", mapping[&Author::new("Author A")].name);
+		// "two" and "three" have the same contribution, the tie is broken by name
+		assert_eq!("three", mapping[&Author::new("Author B")].name);
+		assert_eq!("two", mapping[&Author::new("Author C")].name);
+
+		let global_stats = anonymized.global_stats(SortStatsBy::Commits);
+		assert_eq!("Author A", global_stats[0].author.name);
+		assert_eq!(None, global_stats[0].author.email);
+		assert_eq!(2, global_stats[0].commits_count);
+
+		let commits = (0..28)
+			.map(|index| commit_detail(Author::new(format!("author {index:02}")), 1704103200 + index, stats))
+			.collect::<Vec<_>>();
+		let (_, mapping) = commits.commits_per_author().anonymize();
+		assert_eq!("author 25", mapping[&Author::new("Author Z")].name);
+		assert_eq!("author 27", mapping[&Author::new("Author AB")].name);
+	}
+
+	#[test]
+	fn test_deserialize_round_trip() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 2,
+			lines_deleted: 1,
+		};
+		let commits = vec![
+			commit_detail(Author::new("Jane Doe").with_email("jane@example.com"), 1704103200, stats),
+			commit_detail(Author::new("one"), 1704207600, stats),
+			commit_detail(Author::new("Jane Doe").with_email("jane@example.com"), 1706954400, stats),
+		];
+
+		let commits_per_author = commits.clone().commits_per_author();
+		let json = serde_json::to_string(&commits_per_author).unwrap();
+		assert!(json.contains("\"Jane Doe <jane@example.com>\":"), "{json}");
+		let deserialized: CommitsPerAuthor = serde_json::from_str(&json).unwrap();
+		assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+		let jane = Author::new("Jane Doe").with_email("jane@example.com");
+		assert_eq!(2, deserialized.detailed_stats()[&jane].len());
+		assert_eq!(1, deserialized.detailed_stats()[&Author::new("one")].len());
+
+		let json = serde_json::to_string(&commits.clone().commits_per_month()).unwrap();
+		let deserialized: CommitsPerMonth = serde_json::from_str(&json).unwrap();
+		assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+
+		let json = serde_json::to_string(&commits.clone().commits_per_weekday()).unwrap();
+		// the authors of the inner maps are string keys too
+		let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+		assert!(value["0"]["Jane Doe <jane@example.com>"].is_object(), "{json}");
+		let deserialized: CommitsPerWeekday = serde_json::from_str(&json).unwrap();
+		assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+
+		let json = serde_json::to_string(&commits.clone().commits_heatmap()).unwrap();
+		let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+		assert!(value["Jane Doe <jane@example.com>"].is_array(), "{json}");
+		let deserialized: CommitsHeatMap = serde_json::from_str(&json).unwrap();
+		assert_eq!(json, serde_json::to_string(&deserialized).unwrap());
+
+		let global_stats = commits_per_author.global_stats(SortStatsBy::Commits);
+		let json = serde_json::to_string(&global_stats).unwrap();
+		let deserialized: Vec<GlobalStat> = serde_json::from_str(&json).unwrap();
+		assert_eq!(jane, deserialized[0].author);
+		assert_eq!(4, deserialized[0].stats.lines_added);
+
+		assert!(serde_json::from_str::<CommitsPerWeekday>("{\"monday\":{}}").is_err());
+
+		// the commits serialized before the committer date, the co-authors and the files were collected
+		let json = r#"{"hash":"a9ae91e","author":{"name":"one","email":null},"author_timestamp":1704103200,
+			"stats":{"files_changed":1,"lines_added":2,"lines_deleted":1}}"#;
+		let commit: CommitDetail = serde_json::from_str(json).unwrap();
+		assert_eq!(0, commit.committer_timestamp);
+		assert!(commit.co_authors.is_empty());
+		assert!(commit.files.is_empty());
+		assert!(!commit.truncated);
+
+		// the global stats serialized before the distinct files and the first and last commits
+		let json = r#"[{"author":{"name":"one","email":null},"commits_count":1,
+			"stats":{"files_changed":1,"lines_added":2,"lines_deleted":1}}]"#;
+		let global_stats: Vec<GlobalStat> = serde_json::from_str(json).unwrap();
+		assert_eq!(0, global_stats[0].distinct_files);
+		assert_eq!(0, global_stats[0].first_commit);
+
+		// an author without commits has no global stats
+		let deserialized: CommitsPerAuthor = serde_json::from_str(r#"{"one":[],"two <two@example.com>":[]}"#).unwrap();
+		assert!(deserialized.global_stats(SortStatsBy::Commits).is_empty());
+		assert!(deserialized.top_n(1, SortStatsBy::Commits).is_empty());
+	}
+
+	#[test]
+	fn test_accumulate() {
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, CommitStats::default()),
+			commit_detail(Author::new("two"), 1704207600, CommitStats::default()),
+			commit_detail(Author::new("one"), 1706954400, CommitStats::default()),
+			commit_detail(Author::new("one"), 1706958000, CommitStats::default()),
+		];
+
+		let mut heatmap = CommitsHeatMap::default();
+		let mut weekdays = CommitsPerWeekday::default();
+		let mut months = CommitsPerMonth::default();
+		for commit in commits.iter() {
+			heatmap.accumulate(commit);
+			weekdays.accumulate(commit);
+			months.accumulate(commit);
+		}
+
+		let batch = commits.clone().commits_heatmap();
+		assert_eq!(serde_json::to_string(&batch).unwrap(), serde_json::to_string(&heatmap).unwrap());
+		assert_eq!(2, heatmap.detailed_stats().len());
+		let batch = commits.clone().commits_per_weekday();
+		assert_eq!(serde_json::to_string(&batch).unwrap(), serde_json::to_string(&weekdays).unwrap());
+		// 2024-01 and 2024-02 are contiguous, so there are no empty months in the batch aggregation
+		let batch = commits.commits_per_month();
+		assert_eq!(serde_json::to_string(&batch).unwrap(), serde_json::to_string(&months).unwrap());
+	}
+
+	#[test]
+	fn test_heatmap_to_markdown() {
+		// 2024-01-01 (Mon) 10:00, 2024-01-02 (Tue) 15:00, 2024-01-08 (Mon) 10:00
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, CommitStats::default()),
+			commit_detail(Author::new("two"), 1704207600, CommitStats::default()),
+			commit_detail(Author::new("one"), 1704708000, CommitStats::default()),
+		];
+		let markdown = commits.commits_heatmap().to_markdown();
+		let lines = markdown.lines().collect::<Vec<_>>();
+
+		assert_eq!(9, lines.len());
+		assert!(lines[0].starts_with("| Weekday/Hour | 0 | 1 | 2 |"));
+		assert!(lines[0].ends_with("| 22 | 23 |"));
+		assert_eq!(25, lines[1].matches("---").count());
+		assert_eq!(
+			"| Mon | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 2 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 | 0 |",
+			lines[2]
+		);
+		assert!(lines[3].starts_with("| Tue |"));
+		assert_eq!(Some("1"), lines[3].split(" | ").nth(16));
+		assert!(lines[8].starts_with("| Sun |"));
+	}
+
+	#[test]
+	fn test_heatmap_week_start() {
+		// 2024-01-01 (Mon) 10:00, 2024-01-07 (Sun) 08:00, 2024-01-06 (Sat) 23:00
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, CommitStats::default()),
+			commit_detail(Author::new("two"), 1704614400, CommitStats::default()),
+			commit_detail(Author::new("one"), 1704582000, CommitStats::default()),
+		];
+		let heatmap = commits.clone().commits_heatmap();
+		let monday_first = heatmap.global_stats_week_start(WeekStart::Monday);
+		assert_eq!(1, monday_first[0][10].commits_count);
+		assert_eq!(1, monday_first[6][8].commits_count);
+
+		let sunday_first = heatmap.global_stats_week_start(WeekStart::Sunday);
+		assert_eq!(7, sunday_first.len());
+		assert_eq!(1, sunday_first[0][8].commits_count);
+		assert_eq!(1, sunday_first[1][10].commits_count);
+		assert_eq!(1, sunday_first[6][23].commits_count);
+
+		assert_eq!(Weekday::Mon, WeekStart::Monday.weekdays()[0]);
+		assert_eq!(
+			[Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat],
+			WeekStart::Sunday.weekdays()
+		);
+	}
+
+	#[test]
+	fn test_merge_by_email() {
+		let stats = CommitStats::default();
+		let minimal = |timestamp: i64| -> MinimalCommitDetail { commit_detail(Author::new(""), timestamp, stats).into() };
+		let maiden = Author::new("Jane Smith").with_email("jane@example.com");
+		let married = Author::new("Jane Doe").with_email("Jane@Example.com");
+		let other = Author::new("Jane Smith").with_email("smith@example.com");
+		let no_email = Author::new("Jane Doe");
+		let commits_per_author = CommitsPerAuthor(HashMap::from([
+			(maiden.clone(), vec![minimal(1704103200), minimal(1704207600)]),
+			(married.clone(), vec![minimal(1706954400)]),
+			(other.clone(), vec![minimal(1704110400)]),
+			(no_email.clone(), vec![minimal(1704114000)]),
+		]));
+
+		let merged = commits_per_author.merge_by_email();
+		let detailed_stats = merged.detailed_stats();
+		assert_eq!(3, detailed_stats.len());
+		let (canonical, commits) = detailed_stats.get_key_value(&married).unwrap();
+		assert_eq!("Jane Doe", canonical.name);
+		assert_eq!(
+			vec![1704103200, 1704207600, 1706954400],
+			commits.iter().map(|c| c.author_timestamp).collect::<Vec<_>>()
+		);
+		assert!(!detailed_stats.contains_key(&maiden));
+		assert_eq!(1, detailed_stats[&other].len());
+		assert_eq!(1, detailed_stats[&no_email].len());
+	}
+
+	#[test]
+	fn test_top_n() {
+		let stats = |lines_added| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted: 0,
+		};
+		let commits = (0..20)
+			.flat_map(|index: u64| {
+				(0..=index % 7).map(move |n| {
+					commit_detail(Author::new(format!("author {index:02}")), 1704103200 + n as i64, stats(index * 3 % 11))
+				})
+			})
+			.collect::<Vec<_>>();
+		let commits_per_author = commits.commits_per_author();
+
+		for sort in [SortStatsBy::Commits, SortStatsBy::LinesAdded, SortStatsBy::Name] {
+			let names = |stats: Vec<GlobalStat>| stats.into_iter().map(|s| s.author.name).collect::<Vec<_>>();
+			let all = names(commits_per_author.global_stats(sort));
+			assert_eq!(all[..5], names(commits_per_author.top_n(5, sort)));
+			assert_eq!(all, names(commits_per_author.top_n(50, sort)));
+			assert!(commits_per_author.top_n(0, sort).is_empty());
+		}
+	}
+
+	#[test]
+	fn test_part_of_day_leaders() {
+		let stats = CommitStats::default();
+		// 2024-01-01 at 08:00, 09:00, 14:00, 19:00, 23:00 and 03:00 (UTC)
+		let commits = vec![
+			commit_detail(Author::new("early"), 1704096000, stats),
+			commit_detail(Author::new("early"), 1704099600, stats),
+			commit_detail(Author::new("late"), 1704099600, stats),
+			commit_detail(Author::new("late"), 1704117600, stats),
+			commit_detail(Author::new("early"), 1704117600, stats),
+			commit_detail(Author::new("late"), 1704135600, stats),
+			commit_detail(Author::new("late"), 1704150000, stats),
+			commit_detail(Author::new("late"), 1704078000, stats),
+		];
+
+		let leaders = commits.commits_per_day_hour().part_of_day_leaders();
+		assert_eq!(4, leaders.len());
+		assert_eq!("early", leaders["morning"].name);
+		// one commit each, the tie is broken by name
+		assert_eq!("early", leaders["afternoon"].name);
+		assert_eq!("late", leaders["evening"].name);
+		assert_eq!("late", leaders["night"].name);
+
+		let leaders = vec![commit_detail(Author::new("early"), 1704096000, stats)].commits_per_day_hour();
+		assert_eq!(vec!["morning"], leaders.part_of_day_leaders().into_keys().collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn test_peak_and_quietest_hour() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 10,
+			lines_deleted: 2,
+		};
+		// 2024-01-01 at 09:00 (twice), 14:00 (twice) and 03:00 (UTC)
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704099600, stats),
+			commit_detail(Author::new("two"), 1704099660, stats),
+			commit_detail(Author::new("one"), 1704117600, stats),
+			commit_detail(Author::new("two"), 1704117660, stats),
+			commit_detail(Author::new("one"), 1704078000, stats),
+		];
+		let per_hour = commits.commits_per_day_hour();
+		// 09:00 and 14:00 are tied, the lowest hour wins
+		let (hour, peak) = per_hour.peak_hour();
+		assert_eq!(9, hour);
+		assert_eq!(2, peak.commits_count);
+		assert_eq!(20, peak.stats.lines_added);
+		assert_eq!((0, SimpleStat::default()), per_hour.quietest_hour());
+
+		let every_hour = (0..24).map(|hour| commit_detail(Author::new("one"), 1704067200 + hour * 3600, stats));
+		let mut per_hour = every_hour.collect::<Vec<_>>();
+		per_hour.push(commit_detail(Author::new("two"), 1704067200, stats));
+		let per_hour = per_hour.commits_per_day_hour();
+		assert_eq!(0, per_hour.peak_hour().0);
+		assert_eq!((1, 1), (per_hour.quietest_hour().0, per_hour.quietest_hour().1.commits_count));
+
+		let empty = Vec::<CommitDetail>::new().commits_per_day_hour();
+		assert_eq!((0, SimpleStat::default()), empty.peak_hour());
+		assert_eq!((0, SimpleStat::default()), empty.quietest_hour());
+	}
+
+	#[test]
+	fn test_commits_per_author_by() {
+		let stats = CommitStats::default();
+		let commits = vec![
+			commit_detail(Author::new("Jane").with_email("jane@acme.com"), 1704103200, stats),
+			commit_detail(Author::new("John").with_email("john@ACME.com"), 1704106800, stats),
+			commit_detail(Author::new("Jane Doe").with_email("Jane@acme.com"), 1704110400, stats),
+			commit_detail(Author::new("Bob").with_email("bob@example.com"), 1704114000, stats),
+			commit_detail(Author::new("Nobody"), 1704117600, stats),
+		];
+
+		let domain = |author: &Author| {
+			let email = author.email.as_deref().unwrap_or_default();
+			email.rsplit_once('@').map(|(_, domain)| domain.to_lowercase()).unwrap_or_default()
+		};
+		let per_domain = commits.commits_per_author_by(domain);
+		let counts = per_domain
+			.global_stats(SortStatsBy::Commits)
+			.into_iter()
+			.map(|stat| (domain(&stat.author), stat.commits_count))
+			.collect::<Vec<_>>();
+		assert_eq!(vec![("acme.com".to_string(), 3), ("example.com".to_string(), 1), ("".to_string(), 1)], counts);
+		// the representative is the smallest identity of the group
+		assert_eq!("Jane", per_domain.global_stats(SortStatsBy::Commits)[0].author.name);
+
+		// grouping by the lowercase email is the same as merging by email, but for the chosen name
+		let per_email = commits.commits_per_author_by(|author| author.email.as_deref().unwrap_or("").to_lowercase());
+		let merged = commits.commits_per_author().merge_by_email();
+		let counts = |stats: Vec<GlobalStat>| {
+			stats.into_iter().map(|s| (s.email().to_lowercase(), s.commits_count)).collect::<Vec<_>>()
+		};
+		assert_eq!(
+			counts(merged.global_stats(SortStatsBy::Email)),
+			counts(per_email.global_stats(SortStatsBy::Email))
+		);
+	}
+
+	#[test]
+	fn test_stats_ratios() {
+		let stats = CommitStats {
+			files_changed: 2,
+			lines_added: 40,
+			lines_deleted: 50,
+		};
+		assert_eq!(-10, stats.net_lines());
+		assert_eq!(90, stats.total_lines());
+		assert_eq!(1.25, stats.churn_ratio());
+
+		let max = CommitStats {
+			files_changed: 1,
+			lines_added: u64::MAX,
+			lines_deleted: u64::MAX,
+		};
+		assert_eq!(0, max.net_lines());
+		assert_eq!(u64::MAX, max.total_lines());
+		let only_added = CommitStats {
+			lines_deleted: 0,
+			..max
+		};
+		assert_eq!(i64::MAX, only_added.net_lines());
+
+		let only_deleted = CommitStats {
+			files_changed: 1,
+			lines_added: 0,
+			lines_deleted: 7,
+		};
+		assert_eq!(0.0, only_deleted.churn_ratio());
+		assert_eq!(0.0, CommitStats::default().churn_ratio());
+
+		let simple = SimpleStat {
+			commits_count: 3,
+			stats: stats + only_deleted,
+		};
+		assert_eq!(-17, simple.net_lines());
+		assert_eq!(97, simple.total_lines());
+		assert_eq!(57.0 / 40.0, simple.churn_ratio());
+	}
+
+	#[test]
+	fn test_stats_past_u32_max() {
+		let big = CommitStats {
+			files_changed: u32::MAX as u64,
+			lines_added: u32::MAX as u64,
+			lines_deleted: 1,
+		};
+		let total = big + big + big;
+		assert_eq!(3 * u32::MAX as u64, total.files_changed);
+		assert_eq!(3 * u32::MAX as u64, total.lines_added);
+		assert_eq!(3, total.lines_deleted);
+
+		let alice = Author::new("Alice");
+		let commits = (0..3).map(|index| commit_detail(alice.clone(), index * 3600, big)).collect::<Vec<_>>();
+		let per_author = commits.commits_per_author();
+		let global = per_author.global_stats(SortStatsBy::Commits);
+		assert_eq!(1, global.len());
+		assert_eq!(3, global[0].commits_count);
+		assert_eq!(total, global[0].stats);
+		assert_eq!(3 * u32::MAX as u64 + 3, global[0].stats.total_lines());
+	}
+
+	#[test]
+	fn test_stats_subtraction() {
+		let a = CommitStats {
+			files_changed: 3,
+			lines_added: 120,
+			lines_deleted: 40,
+		};
+		let b = CommitStats {
+			files_changed: 5,
+			lines_added: 10,
+			lines_deleted: 60,
+		};
+		assert_eq!(a, (a + b) - b);
+		assert_eq!(
+			CommitStats {
+				files_changed: 0,
+				lines_added: 110,
+				lines_deleted: 0,
+			},
+			a - b
+		);
+
+		let mut stats = a;
+		stats -= a;
+		assert_eq!(CommitStats::default(), stats);
+
+		let current = SimpleStat {
+			commits_count: 4,
+			stats: a,
+		};
+		let previous = SimpleStat {
+			commits_count: 7,
+			stats: b,
+		};
+		assert_eq!(current, (current.clone() + previous.clone()) - previous.clone());
+		let delta = current.clone() - previous.clone();
+		assert_eq!(0, delta.commits_count);
+		assert_eq!(a - b, delta.stats);
+
+		let mut stats = previous.clone();
+		stats -= current;
+		assert_eq!(3, stats.commits_count);
+		assert_eq!(b - a, stats.stats);
+	}
+
+	#[test]
+	fn test_streaks() {
+		const DAY: i64 = 86_400;
+		let start = 1704067200; // 2024-01-01 00:00:00 UTC
+		let alice = Author::new("Alice").with_email("alice@example.com");
+		let bob = Author::new("Bob").with_email("bob@example.com");
+		let stats = CommitStats::default();
+		let commits = vec![
+			// two commits on the same day, the second one right before midnight
+			commit_detail(alice.clone(), start + 3600, stats),
+			commit_detail(alice.clone(), start + DAY - 1, stats),
+			commit_detail(alice.clone(), start + DAY + 3600, stats),
+			commit_detail(alice.clone(), start + 2 * DAY + 3600, stats),
+			commit_detail(alice.clone(), start + 4 * DAY + 3600, stats),
+			commit_detail(alice.clone(), start + 5 * DAY + 7200, stats),
+			commit_detail(bob.clone(), start + 7200, stats),
+			commit_detail(bob.clone(), start + DAY + 7200, stats),
+		];
+		let streaks = commits.commits_per_author().streaks();
+
+		assert_eq!(
+			StreakInfo {
+				longest_consecutive_days: 3,
+				current_streak: 2,
+				active_days: 5,
+				first_commit: start + 3600,
+				last_commit: start + 5 * DAY + 7200,
+			},
+			streaks[&alice]
+		);
+		assert_eq!(
+			StreakInfo {
+				longest_consecutive_days: 2,
+				current_streak: 0,
+				active_days: 2,
+				first_commit: start + 7200,
+				last_commit: start + DAY + 7200,
+			},
+			streaks[&bob]
+		);
+	}
+
+	#[test]
+	fn test_lines_per_commit() {
+		let stats = |lines_added, lines_deleted| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted,
+		};
+		let one = Author::new("one");
+		let two = Author::new("two");
+		let commits = vec![
+			commit_detail(one.clone(), 1704103200, stats(10, 0)),
+			commit_detail(one.clone(), 1704106800, stats(5, 5)),
+			commit_detail(one.clone(), 1704110400, stats(1000, 200)),
+			commit_detail(two.clone(), 1704114000, stats(3, 1)),
+			commit_detail(two.clone(), 1704117600, stats(6, 0)),
+		];
+		let per_author = commits.commits_per_author();
+
+		let global_stats = per_author.global_stats(SortStatsBy::Name);
+		assert_eq!(1220.0 / 3.0, global_stats[0].avg_lines_per_commit());
+		assert_eq!(5.0, global_stats[1].avg_lines_per_commit());
+		let no_commits = GlobalStat {
+			commits_count: 0,
+			..global_stats[0].clone()
+		};
+		assert_eq!(0.0, no_commits.avg_lines_per_commit());
+
+		// the huge commit doesn't move the median
+		let median = per_author.median_lines_per_commit();
+		assert_eq!(Some(&10.0), median.get(&one));
+		assert_eq!(Some(&5.0), median.get(&two));
+	}
+
+	#[test]
+	fn test_bus_factor() {
+		let stats = |lines_added, lines_deleted| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted,
+		};
+		let commits = vec![
+			commit_detail(Author::new("Alice").with_email("alice@example.com"), 1704103200, stats(30, 10)),
+			commit_detail(Author::new("Alice").with_email("alice@example.com"), 1704106800, stats(10, 0)),
+			commit_detail(Author::new("Bob").with_email("bob@example.com"), 1704110400, stats(20, 10)),
+			commit_detail(Author::new("Carol").with_email("carol@example.com"), 1704114000, stats(15, 5)),
+		];
+		let commits_per_author = commits.commits_per_author();
+
+		// Alice 50 lines, Bob 30, Carol 20
+		assert_eq!(1, commits_per_author.bus_factor(0.5));
+		assert_eq!(2, commits_per_author.bus_factor(0.51));
+		assert_eq!(2, commits_per_author.bus_factor(0.8));
+		assert_eq!(3, commits_per_author.bus_factor(0.9));
+		assert_eq!(3, commits_per_author.bus_factor(1.5));
+		assert_eq!(0, commits_per_author.bus_factor(0.0));
+
+		let empty = vec![commit_detail(Author::new("Alice"), 1704103200, stats(0, 0))];
+		assert_eq!(0, empty.commits_per_author().bus_factor(0.5));
+	}
+
+	#[test]
+	fn test_global_stats_first_last_commit() {
+		let alice = Author::new("Alice").with_email("alice@example.com");
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 1,
+			lines_deleted: 0,
+		};
+		let commits = vec![
+			commit_detail(alice.clone(), 1704193200, stats),
+			commit_detail(alice.clone(), 1704103200, stats),
+			commit_detail(alice.clone(), 1706781600, stats),
+			commit_detail(Author::new("Bob"), 1704110400, stats),
+		];
+		let global_stats = commits.commits_per_author().global_stats(SortStatsBy::Commits);
+
+		assert_eq!(alice, global_stats[0].author);
+		assert_eq!(1704103200, global_stats[0].first_commit);
+		assert_eq!(1706781600, global_stats[0].last_commit);
+		assert_eq!(1704110400, global_stats[1].first_commit);
+		assert_eq!(1704110400, global_stats[1].last_commit);
+		assert!(global_stats[0].to_string().ends_with("first commit: 2024-01-01, last commit: 2024-02-01"));
+	}
+
+	#[test]
+	fn test_churn_per_author() {
+		const HOUR: i64 = 3600;
+		let file = |path: &str, lines_added: u64| FileStat {
+			path: path.to_string(),
+			lines_added,
+			lines_deleted: 0,
+		};
+		let commit = |author: &Author, timestamp: i64, files: Vec<FileStat>| {
+			let mut commit = commit_detail(author.clone(), timestamp, CommitStats::default());
+			commit.files = files;
+			commit
+		};
+		let alice = Author::new("Alice");
+		let bob = Author::new("Bob");
+		let start = 1704103200;
+		let commits = vec![
+			// a.rs is rewritten by Bob 2 hours later, b.rs is never touched again
+			commit(&alice, start, vec![file("a.rs", 10), file("b.rs", 5)]),
+			commit(&bob, start + 2 * HOUR, vec![file("a.rs", 4)]),
+			// c.rs is changed again only 3 days later
+			commit(&alice, start + 3 * HOUR, vec![file("c.rs", 7)]),
+			commit(&alice, start + 75 * HOUR, vec![file("c.rs", 1)]),
+		];
+
+		let churn = commits.churn_per_author(Duration::from_secs(24 * 3600));
+		assert_eq!(
+			Churn {
+				productive_lines: 13,
+				churned_lines: 10,
+			},
+			churn[&alice]
+		);
+		assert_eq!(
+			Churn {
+				productive_lines: 4,
+				churned_lines: 0,
+			},
+			churn[&bob]
+		);
+
+		// with a longer window c.rs is churned too
+		let churn = commits.churn_per_author(Duration::from_secs(7 * 24 * 3600));
+		assert_eq!(17, churn[&alice].churned_lines);
+		assert_eq!(6, churn[&alice].productive_lines);
+	}
+
+	#[test]
+	fn test_global_stats_distinct_files() {
+		let commit = |author: &Author, timestamp: i64, paths: &[&str]| {
+			let mut commit = commit_detail(
+				author.clone(),
+				timestamp,
+				CommitStats {
+					files_changed: paths.len() as u64,
+					lines_added: 1,
+					lines_deleted: 0,
+				},
+			);
+			commit.files = paths
+				.iter()
+				.map(|path| FileStat {
+					path: path.to_string(),
+					lines_added: 1,
+					lines_deleted: 0,
+				})
+				.collect();
+			commit
+		};
+		let alice = Author::new("Alice");
+		let bob = Author::new("Bob");
+		let commits = vec![
+			commit(&alice, 1704103200, &["src/lib.rs", "README.md"]),
+			commit(&alice, 1704106800, &["src/lib.rs"]),
+			commit(&alice, 1704110400, &["src/lib.rs", "src/main.rs"]),
+			commit(&bob, 1704114000, &["src/lib.rs"]),
+			commit_detail(bob.clone(), 1704117600, CommitStats::default()),
+		];
+		let global_stats = commits.commits_per_author().global_stats(SortStatsBy::Commits);
+
+		assert_eq!(alice, global_stats[0].author);
+		assert_eq!(5, global_stats[0].stats.files_changed);
+		assert_eq!(3, global_stats[0].distinct_files);
+		assert_eq!(bob, global_stats[1].author);
+		assert_eq!(1, global_stats[1].distinct_files);
+	}
+
+	#[test]
+	fn test_weekend_vs_weekday() {
+		let stats = |lines_added| CommitStats {
+			files_changed: 1,
+			lines_added,
+			lines_deleted: 0,
+		};
+		// 2024-01-01 (Mon), 2024-01-05 (Fri), 2024-01-06 (Sat), 2024-01-07 (Sun), all at 10:00
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, stats(1)),
+			commit_detail(Author::new("two"), 1704448800, stats(2)),
+			commit_detail(Author::new("one"), 1704535200, stats(4)),
+			commit_detail(Author::new("two"), 1704621600, stats(8)),
+		];
+		let commits_per_weekday = commits.commits_per_weekday();
+
+		let (weekday, weekend) = commits_per_weekday.weekend_vs_weekday(&Weekend::default());
+		assert_eq!((2, 3), (weekday.commits_count, weekday.stats.lines_added));
+		assert_eq!((2, 12), (weekend.commits_count, weekend.stats.lines_added));
+
+		let (weekday, weekend) = commits_per_weekday.weekend_vs_weekday(&Weekend::friday_saturday());
+		assert_eq!((2, 9), (weekday.commits_count, weekday.stats.lines_added));
+		assert_eq!((2, 6), (weekend.commits_count, weekend.stats.lines_added));
+
+		let (weekday, weekend) = commits_per_weekday.weekend_vs_weekday(&Weekend::new(&[]));
+		assert_eq!(4, weekday.commits_count);
+		assert_eq!(SimpleStat::default(), weekend);
+	}
+
+	#[test]
+	fn test_commits_per_month_moving_average() {
+		let mut commits_per_month = CommitsPerMonth::default();
+		// 3 commits in 2023-11, none in 2023-12, 1 in 2024-01 and 2 in 2024-02
+		for timestamp in [1698832800, 1698919200, 1699005600, 1704103200, 1706781600, 1706868000] {
+			commits_per_month.accumulate(&commit_detail(Author::new("one"), timestamp, CommitStats::default()));
+		}
+
+		let raw = commits_per_month.moving_average(1);
+		assert_eq!(
+			vec![
+				("2023-11".to_string(), 3.0),
+				("2023-12".to_string(), 0.0),
+				("2024-01".to_string(), 1.0),
+				("2024-02".to_string(), 2.0)
+			],
+			raw
+		);
+		assert_eq!(raw, commits_per_month.moving_average(0));
+
+		let smoothed = commits_per_month.moving_average(2);
+		assert_eq!(vec![3.0, 1.5, 0.5, 1.5], smoothed.iter().map(|(_, value)| *value).collect::<Vec<_>>());
+		let smoothed = commits_per_month.moving_average(3);
+		assert_eq!(vec![3.0, 1.5, 4.0 / 3.0, 1.0], smoothed.iter().map(|(_, value)| *value).collect::<Vec<_>>());
+
+		assert!(CommitsPerMonth::default().moving_average(3).is_empty());
+	}
+
+	#[test]
+	fn test_commits_per_month_fills_gaps() {
+		// 2023-11-01, 2024-02-10 and 2023-11-20: the input is not sorted
+		let commits = vec![
+			commit_detail(Author::new("one"), 1698832800, CommitStats::default()),
+			commit_detail(Author::new("two"), 1707559200, CommitStats::default()),
+			commit_detail(Author::new("one"), 1700474400, CommitStats::default()),
+		];
+		let commits_per_month = commits.commits_per_month();
+		let mut keys = commits_per_month.detailed_stats().keys().cloned().collect::<Vec<_>>();
+		keys.sort();
+		assert_eq!(vec!["2023-11", "2023-12", "2024-01", "2024-02"], keys);
+		assert!(commits_per_month.detailed_stats()["2023-12"].is_empty());
+		assert!(commits_per_month.detailed_stats()["2024-01"].is_empty());
+
+		let global_stats = commits_per_month.global_stats();
+		assert_eq!(2, global_stats["2023-11"].commits_count);
+		assert_eq!(SimpleStat::default(), global_stats["2023-12"]);
+		assert_eq!(1, global_stats["2024-02"].commits_count);
+
+		// a single commit has its own month
+		let commits = vec![commit_detail(Author::new("one"), 1698832800, CommitStats::default())];
+		let commits_per_month = commits.commits_per_month();
+		assert_eq!(1, commits_per_month.global_stats()["2023-11"].commits_count);
+		assert_eq!(1, commits_per_month.detailed_stats().len());
+
+		assert!(Vec::<CommitDetail>::new().commits_per_month().detailed_stats().is_empty());
+	}
+
+	#[test]
+	fn test_total() {
+		let stats = |files_changed, lines_added, lines_deleted| CommitStats {
+			files_changed,
+			lines_added,
+			lines_deleted,
+		};
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, stats(1, 10, 2)),
+			commit_detail(Author::new("two"), 1704106800, stats(3, 5, 7)),
+			commit_detail(Author::new("one"), 1704110400, stats(2, 0, 1)),
+		];
+		let expected = SimpleStat {
+			commits_count: 3,
+			stats: stats(6, 15, 10),
+		};
+		assert_eq!(expected, commits.total());
+		assert_eq!(expected, commits.into_iter().collect::<SimpleStat>());
+		assert_eq!(SimpleStat::default(), Vec::<CommitDetail>::new().total());
+	}
+
+	#[test]
+	fn test_heatmap_normalized_global() {
+		// two commits on 2024-01-01 (Mon) 10:00, one on 2024-01-07 (Sun) 08:00
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704103200, CommitStats::default()),
+			commit_detail(Author::new("two"), 1704103260, CommitStats::default()),
+			commit_detail(Author::new("one"), 1704614400, CommitStats::default()),
+		];
+		let normalized = commits.commits_heatmap().normalized_global();
+		assert_eq!(7, normalized.len());
+		assert!(normalized.iter().all(|row| row.len() == 24));
+		assert_eq!(1.0, normalized[0][10]);
+		assert_eq!(0.5, normalized[6][8]);
+		assert_eq!(1.5, normalized.iter().flatten().sum::<f64>());
+
+		let empty = Vec::<CommitDetail>::new().commits_heatmap().normalized_global();
+		assert_eq!(7, empty.len());
+		assert!(empty.iter().flatten().all(|value| *value == 0.0));
+	}
+
+	#[test]
+	fn test_heatmap_busiest_slot() {
+		let stats = CommitStats {
+			files_changed: 1,
+			lines_added: 3,
+			lines_deleted: 0,
+		};
+		// two commits on 2024-01-01 (Mon) 10:00, two on 2024-01-07 (Sun) 08:00
+		let commits = vec![
+			commit_detail(Author::new("one"), 1704614400, stats),
+			commit_detail(Author::new("one"), 1704103200, stats),
+			commit_detail(Author::new("two"), 1704103260, stats),
+			commit_detail(Author::new("one"), 1704614460, stats),
+		];
+		let heatmap = commits.commits_heatmap();
+		// the tie is broken by the earliest slot of the week
+		let (weekday, hour, stat) = heatmap.busiest_slot();
+		assert_eq!((Weekday::Mon, 10, 2, 6), (weekday, hour, stat.commits_count, stat.stats.lines_added));
+		let (weekday, hour, stat) = heatmap.peak_slot(&Author::new("one")).unwrap();
+		assert_eq!((Weekday::Sun, 8, 2), (weekday, hour, stat.commits_count));
+		assert_eq!(Weekday::Mon, heatmap.peak_slot(&Author::new("two")).unwrap().0);
+		assert!(heatmap.peak_slot(&Author::new("three")).is_none());
+
+		let empty = Vec::<CommitDetail>::new().commits_heatmap();
+		assert_eq!((Weekday::Mon, 0, SimpleStat::default()), empty.busiest_slot());
+	}
+
+	fn commit_detail(author: Author, author_timestamp: i64, stats: CommitStats) -> CommitDetail {
+		CommitDetail {
+			hash: CommitHash(format!("{:040x}", author_timestamp)),
+			author,
+			author_timestamp,
+			committer_timestamp: author_timestamp,
+			stats,
+			co_authors: vec![],
+			files: vec![],
+			renames: vec![],
+			truncated: false,
 		}
 	}
 }
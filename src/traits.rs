@@ -1,4 +1,12 @@
-use crate::{CommitsHeatMap, CommitsPerAuthor, CommitsPerDayHour, CommitsPerMonth, CommitsPerWeekday};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::FixedOffset;
+
+use crate::{
+	Author, Churn, CoAuthorMode, CommitsHeatMap, CommitsPerAuthor, CommitsPerDayHour, CommitsPerMonth, CommitsPerWeek,
+	CommitsPerWeekday, LandingDelayStats, SimpleStat, SizeHistogram, StatsPerExtension,
+};
 
 pub trait CommitStatsExt {
 	/// Return the commits per author
@@ -7,7 +15,10 @@ pub trait CommitStatsExt {
 	/// ```rust
 	///
 	/// use comfy_table::Table;
+	/// # #[cfg(feature = "repo")]
 	/// use gitstats::{CommitArgs, Repo, SortStatsBy};
+	/// use gitstats::traits::CommitStatsExt;
+	/// # #[cfg(feature = "repo")]
 	/// fn contributors_stats() {
 	/// 	let repo = Repo::new("/custom/repo");
 	/// 	let commits = repo.list_commits(CommitArgs::default()).unwrap();
@@ -32,7 +43,7 @@ pub trait CommitStatsExt {
 	///
 	/// It will print something like this:
 	///
-	/// ```
+	/// ```text
 	///
 	/// +---------------------+---------+--------+
 	/// | Author              | Commits | Lines  |
@@ -50,6 +61,37 @@ pub trait CommitStatsExt {
 	/// ```
 	fn commits_per_author(&self) -> CommitsPerAuthor;
 
+	/// Return the commits per author, crediting the `Co-authored-by:` co-authors according to `mode`.
+	/// With [CoAuthorMode::Full] a co-authored commit is double-counted: each participant gets the commit and its
+	/// full stats. With [CoAuthorMode::Split] each participant still gets the commit, but the files and lines
+	/// are divided among them, so the global totals are preserved.
+	fn commits_per_author_with(&self, mode: CoAuthorMode) -> CommitsPerAuthor;
+
+	/// Return the commits per author, grouping the authors by the key returned by `key_fn`
+	/// (e.g. the lowercase email, or the email domain).
+	/// Each group is keyed by its smallest author identity, like in [CommitStatsExt::commits_per_author].
+	///
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::traits::CommitStatsExt;
+	/// use gitstats::{CommitDetail, SortStatsBy};
+	///
+	/// fn commits_per_domain(commits: Vec<CommitDetail>) {
+	/// 	let per_domain = commits.commits_per_author_by(|author| {
+	/// 		let email = author.email.as_deref().unwrap_or_default();
+	/// 		email.rsplit('@').next().unwrap_or_default().to_lowercase()
+	/// 	});
+	/// 	for stat in per_domain.global_stats(SortStatsBy::Commits) {
+	/// 		println!("{}: {}", stat.email(), stat.commits_count);
+	/// 	}
+	/// }
+	/// ```
+	fn commits_per_author_by<F>(&self, key_fn: F) -> CommitsPerAuthor
+	where
+		F: Fn(&Author) -> String;
+
+	/// Return the stats per month (`%Y-%m` keys) and author, from the author date of the commits.
+	/// Every month between the first and the last commit is a key, the months without commits have an empty map.
 	///
 	/// # Examples:
 	/// ```rust
@@ -57,8 +99,11 @@ pub trait CommitStatsExt {
 	/// use chrono::{Months, Utc};
 	/// use itertools::Itertools;
 	/// use textplots::{AxisBuilder, Chart, LabelBuilder, LabelFormat, LineStyle, Plot, Shape, TickDisplay, TickDisplayBuilder};
+	/// # #[cfg(feature = "repo")]
 	/// use gitstats::{CommitArgs, Repo};
+	/// use gitstats::traits::CommitStatsExt;
 	///
+	/// # #[cfg(feature = "repo")]
 	/// fn commits_per_month() {
 	/// 	let repo = Repo::new("/custom/path");
 	/// 	let commits = repo.list_commits(CommitArgs::default()).unwrap();
@@ -104,19 +149,56 @@ pub trait CommitStatsExt {
 	/// ```
 	fn commits_per_month(self) -> CommitsPerMonth;
 
+	/// Same as [CommitStatsExt::commits_per_month], with the months in the timezone with the given `offset` from
+	/// UTC instead of UTC, e.g. to normalize the stats of a distributed team to the office timezone.
+	///
+	/// # Examples:
+	/// ```rust
+	/// use chrono::FixedOffset;
+	/// use gitstats::traits::CommitStatsExt;
+	/// use gitstats::CommitDetail;
+	///
+	/// fn commits_per_month_in_new_york(commits: Vec<CommitDetail>) {
+	/// 	let offset = FixedOffset::west_opt(5 * 3600).unwrap();
+	/// 	for (month, stats) in commits.commits_per_month_in(offset).global_stats() {
+	/// 		println!("{month}: {}", stats.commits_count);
+	/// 	}
+	/// }
+	/// ```
+	fn commits_per_month_in(self, offset: FixedOffset) -> CommitsPerMonth;
+
+	/// Return the stats per ISO week, keyed like `2024-W07`, e.g. for sprint reports.
+	/// The weeks start on Monday and belong to the ISO year of their Thursday, so 2024-12-30 is in `2025-W01`.
+	/// As for [CommitStatsExt::commits_per_month], every week between the first and the last commit is a key,
+	/// even without commits.
+	fn commits_per_week(self) -> CommitsPerWeek;
+
+	/// Same as [CommitStatsExt::commits_per_week], with the weeks in the timezone with the given `offset` from UTC
+	fn commits_per_week_in(self, offset: FixedOffset) -> CommitsPerWeek;
+
 	fn commits_per_weekday(self) -> CommitsPerWeekday;
 
+	/// Same as [CommitStatsExt::commits_per_weekday], with the weekdays in the timezone with the given `offset` from
+	/// UTC: a commit at 01:00 UTC on a Tuesday is on Monday at UTC-05:00
+	fn commits_per_weekday_in(self, offset: FixedOffset) -> CommitsPerWeekday;
+
 	fn commits_per_day_hour(self) -> CommitsPerDayHour;
 
+	/// Same as [CommitStatsExt::commits_per_day_hour], with the hours in the timezone with the given `offset` from UTC
+	fn commits_per_day_hour_in(self, offset: FixedOffset) -> CommitsPerDayHour;
+
 	/// Return a commit heatmap
 	/// # Examples:
 	/// ```rust
 	///
 	/// use chrono::Weekday;
 	/// use comfy_table::Table;
+	/// # #[cfg(feature = "repo")]
 	/// use gitstats::{CommitArgs, Repo};
+	/// use gitstats::traits::CommitStatsExt;
 	/// use num_traits::cast::FromPrimitive;
 	///
+	/// # #[cfg(feature = "repo")]
 	/// fn commits_heatmap() {
 	///	    let repo = Repo::new("/custom/repo");
 	///	    let commits = repo.list_commits(CommitArgs::default()).unwrap();
@@ -177,7 +259,7 @@ pub trait CommitStatsExt {
 	///
 	/// It will print something like this:
 	///
-	/// ```
+	/// ```text
 	///
 	/// +--------------+---+---+---+---+---+---+---+---+----+---+----+----+----+----+----+----+----+----+----+----+----+----+----+----+
 	/// | Weekday/Hour | 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8  | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 |
@@ -199,4 +281,84 @@ pub trait CommitStatsExt {
 	/// ```
 	///
 	fn commits_heatmap(self) -> CommitsHeatMap;
+
+	/// Same as [CommitStatsExt::commits_heatmap], with the weekdays and hours in the timezone with the given `offset`
+	/// from UTC
+	fn commits_heatmap_in(self, offset: FixedOffset) -> CommitsHeatMap;
+
+	/// Return the stats per file extension, computed from the commits changed files.
+	/// The `commits_count` of each extension is the number of commits which touched at least one file with that
+	/// extension, while `files_changed` is the number of changed files.
+	/// Files without an extension are grouped under the `""` key.
+	fn stats_per_extension(self) -> StatsPerExtension;
+
+	/// Return the stats per directory, where the key is made of the first `depth` components of the changed
+	/// files path: with a `depth` of 1 the files are grouped by top-level folder (`src`, `tests`, `docs`...).
+	/// Files shallower than `depth` are grouped under their full path.
+	/// As for [CommitStatsExt::stats_per_extension], `commits_count` is the number of commits touching each key.
+	fn stats_per_directory(self, depth: usize) -> HashMap<String, SimpleStat>;
+
+	/// Return the average, median and max delay between the author date and the committer date of the commits.
+	/// This is a rough proxy of how long the work stays on a branch before it lands (cycle time).
+	fn landing_delay_stats(&self) -> LandingDelayStats;
+
+	/// Classify the commits by the files they touch: test files are the ones matching any of the `test_globs`
+	/// (e.g. `tests/**` or `**/*_test.rs`), all the others are considered source files.
+	/// Returns the number of commits touching only test files, only source files, and both (mixed).
+	/// Commits without per-file stats are not counted, invalid glob patterns are ignored.
+	fn test_coverage_commits(&self, test_globs: &[&str]) -> (usize, usize, usize);
+
+	/// Return the total stats of all the commits, regardless of their author or date
+	fn total(&self) -> SimpleStat;
+
+	/// Estimate the churn of each author: the lines added to a file are considered churned when the same file is
+	/// changed again (by anyone) within `window` after the commit, productive otherwise.
+	/// This is an approximation: the later change may have touched different lines of the file, and the
+	/// deleted lines are ignored. Commits are ordered by author date, and only the per-file stats are used.
+	fn churn_per_author(&self, window: Duration) -> HashMap<Author, Churn>;
+
+	/// Count the commits by size, i.e. their lines added plus deleted, e.g. to draw a histogram or to spot a
+	/// history dominated by huge commits.
+	/// Each threshold starts a new bucket, so [crate::DEFAULT_SIZE_THRESHOLDS] (`[1, 10, 50, 200, 1000]`) gives the
+	/// buckets `0`, `1-9`, `10-49`, `50-199`, `200-999` and `1000+`. The thresholds can be in any order, duplicates
+	/// and `0` are ignored. The counts are returned per author too.
+	///
+	/// # Examples:
+	/// ```rust
+	/// use gitstats::traits::CommitStatsExt;
+	/// use gitstats::{CommitDetail, DEFAULT_SIZE_THRESHOLDS};
+	///
+	/// fn print_histogram(commits: Vec<CommitDetail>) {
+	/// 	for bucket in commits.size_distribution(&DEFAULT_SIZE_THRESHOLDS).buckets {
+	/// 		println!("{bucket}: {}", "#".repeat(bucket.commits_count));
+	/// 	}
+	/// }
+	/// ```
+	fn size_distribution(&self, thresholds: &[u64]) -> SizeHistogram;
+}
+
+/// Combine two results of the same kind, e.g. the stats of several repositories into an organization-wide view.
+/// The same author (see the [Author] equality) is coalesced, and is keyed by the smallest of the merged identities
+/// like in [CommitStatsExt::commits_per_author].
+///
+/// # Examples:
+/// ```rust
+/// use gitstats::traits::{CommitStatsExt, Merge};
+/// use gitstats::{CommitDetail, CommitsPerAuthor, SortStatsBy};
+///
+/// fn org_stats(repositories: Vec<Vec<CommitDetail>>) {
+/// 	let merged = CommitsPerAuthor::merge_all(repositories.iter().map(|commits| commits.commits_per_author()));
+/// 	for stat in merged.global_stats(SortStatsBy::Commits) {
+/// 		println!("{stat}");
+/// 	}
+/// }
+/// ```
+pub trait Merge: Default {
+	/// Returns the union of `self` and `other`
+	fn merge(self, other: Self) -> Self;
+
+	/// Merge all the items, an empty iterator returns the default (empty) value
+	fn merge_all<I: IntoIterator<Item = Self>>(iter: I) -> Self {
+		iter.into_iter().fold(Self::default(), Self::merge)
+	}
 }